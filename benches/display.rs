@@ -0,0 +1,70 @@
+use std::{hint::black_box, time::Instant};
+use yace::display::{Display, HEIGHT, WIDTH};
+
+const ITERATIONS: u32 = 20_000;
+
+// A digit of the font, drawn at every 8th column of each row
+const SPRITE: [u8; 5] = [0xF0, 0x90, 0x90, 0x90, 0xF0];
+
+// The byte per pixel display that the packed rows replaced
+struct PixelDisplay {
+    buffer: [u8; WIDTH * HEIGHT],
+}
+
+impl PixelDisplay {
+    // kept out of line like the library call it is compared to
+    #[inline(never)]
+    fn load_sprite(&mut self, x: usize, y: usize, sprite: &[u8]) -> bool {
+        let mut collision = false;
+
+        for (i, row) in sprite.iter().enumerate() {
+            for offset in 0..8 {
+                let new = row >> (7 - offset) & 1;
+                let index = ((y + i) % HEIGHT) * WIDTH + (x + offset) % WIDTH;
+                let old = self.buffer[index];
+
+                self.buffer[index] = new ^ old;
+                collision |= new == 1 && old == 1;
+            }
+        }
+
+        collision
+    }
+}
+
+fn measure<F: FnMut(usize, usize)>(name: &str, mut draw: F) {
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        for y in 0..HEIGHT {
+            for x in (0..WIDTH).step_by(8) {
+                draw(x + y, y);
+            }
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let draws = ITERATIONS as f64 * (HEIGHT * WIDTH / 8) as f64;
+
+    println!(
+        "{:<10} {:>8.2?} ({:.1} ns per sprite)",
+        name,
+        elapsed,
+        elapsed.as_nanos() as f64 / draws
+    );
+}
+
+// Run with `cargo bench`, the packed display should come out ahead
+fn main() {
+    let mut display = Display::default();
+    let mut pixels = PixelDisplay {
+        buffer: [0; WIDTH * HEIGHT],
+    };
+
+    measure("packed", |x, y| {
+        black_box(display.load_sprite(x, y, black_box(&SPRITE), false));
+    });
+    measure("per pixel", |x, y| {
+        black_box(pixels.load_sprite(x, y, black_box(&SPRITE)));
+    });
+}
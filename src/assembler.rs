@@ -0,0 +1,828 @@
+use crate::{memory::MEM_SIZE, rom::DEFAULT_ADDRESS};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{Error, ErrorKind},
+};
+
+const ENTRY_POINT: &str = "main";
+// Guards against macros that expand themselves
+const MAX_MACRO_DEPTH: usize = 64;
+
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    line: usize,
+    // Number of macro expansions that produced the token
+    depth: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Fixup {
+    // Low 12 bits of an instruction
+    Address,
+    // Two bytes of data
+    Pointer,
+    // Low bytes of the two instructions emitted by :unpack
+    Unpack,
+}
+
+enum Block {
+    If { jump: usize },
+    Else { jump: usize },
+    Loop { start: usize, breaks: Vec<usize> },
+}
+
+struct Macro {
+    args: Vec<String>,
+    body: Vec<Token>,
+}
+
+// Skip instructions that check a condition, followed by the instructions
+// that prepare it
+struct Condition {
+    setup: Vec<u16>,
+    skip_if_false: u16,
+}
+
+impl Condition {
+    fn skip_if_true(&self) -> u16 {
+        match self.skip_if_false & 0xF00F {
+            0x5000 => 0x9000 | (self.skip_if_false & 0x0FF0),
+            0x9000 => 0x5000 | (self.skip_if_false & 0x0FF0),
+            _ => match self.skip_if_false & 0xF0FF {
+                0xE09E => 0xE0A1 | (self.skip_if_false & 0x0F00),
+                0xE0A1 => 0xE09E | (self.skip_if_false & 0x0F00),
+                _ => self.skip_if_false ^ 0x7000,
+            },
+        }
+    }
+}
+
+// Assembles a subset of the Octo language: labels, constants, aliases,
+// control flow, macros and :calc expressions evaluated from right to left
+pub fn assemble(source: &str) -> Result<Vec<u8>, Error> {
+    let mut assembler = Assembler::new(source);
+
+    assembler.run()?;
+    assembler.finish()
+}
+
+struct Assembler {
+    tokens: VecDeque<Token>,
+    rom: Vec<u8>,
+    here: usize,
+    end: usize,
+    line: usize,
+    depth: usize,
+    labels: HashMap<String, usize>,
+    constants: HashMap<String, i64>,
+    aliases: HashMap<String, u8>,
+    macros: HashMap<String, Macro>,
+    fixups: Vec<(usize, String, Fixup, usize)>,
+    blocks: Vec<Block>,
+    next_label: Option<String>,
+}
+
+impl Assembler {
+    fn new(source: &str) -> Self {
+        let tokens = source
+            .lines()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                let code = line.split('#').next().unwrap_or_default();
+
+                code.split_whitespace().map(move |text| Token {
+                    text: text.to_string(),
+                    line: index + 1,
+                    depth: 0,
+                })
+            })
+            .collect();
+        let start = DEFAULT_ADDRESS as usize;
+
+        Self {
+            tokens,
+            rom: vec![0; MEM_SIZE],
+            // the program starts with a jump to main
+            here: start + 2,
+            end: start + 2,
+            line: 1,
+            depth: 0,
+            labels: HashMap::new(),
+            constants: HashMap::new(),
+            aliases: HashMap::new(),
+            macros: HashMap::new(),
+            fixups: vec![(start, ENTRY_POINT.to_string(), Fixup::Address, 1)],
+            blocks: Vec::new(),
+            next_label: None,
+        }
+    }
+
+    fn run(&mut self) -> Result<(), Error> {
+        self.rom[DEFAULT_ADDRESS as usize] = 0x10;
+
+        while !self.tokens.is_empty() {
+            self.statement()?;
+        }
+
+        if !self.blocks.is_empty() {
+            return Err(self.error("Unterminated block"));
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, Error> {
+        if !self.labels.contains_key(ENTRY_POINT) {
+            return Err(self.error("Missing main label"));
+        }
+
+        for (addr, name, fixup, line) in std::mem::take(&mut self.fixups) {
+            self.line = line;
+
+            let value = self
+                .labels
+                .get(&name)
+                .copied()
+                .ok_or_else(|| self.error(&format!("Undefined label: {}", name)))?;
+
+            match fixup {
+                Fixup::Address => {
+                    self.rom[addr] |= (value >> 8) as u8 & 0x0F;
+                    self.rom[addr + 1] = value as u8;
+                }
+                Fixup::Pointer => {
+                    self.rom[addr] = (value >> 8) as u8;
+                    self.rom[addr + 1] = value as u8;
+                }
+                Fixup::Unpack => {
+                    self.rom[addr + 1] |= (value >> 8) as u8 & 0x0F;
+                    self.rom[addr + 3] = value as u8;
+                }
+            }
+        }
+
+        Ok(self.rom[DEFAULT_ADDRESS as usize..self.end].to_vec())
+    }
+
+    fn statement(&mut self) -> Result<(), Error> {
+        let token = self.next()?;
+
+        match token.as_str() {
+            ":" => {
+                let name = self.next()?;
+                self.define_label(name, self.here)?;
+            }
+            ":const" => {
+                let name = self.next()?;
+                let value = self.value()?;
+                self.constants.insert(name, value);
+            }
+            ":alias" => {
+                let name = self.next()?;
+                let register = self.register()?;
+                self.aliases.insert(name, register);
+            }
+            ":unpack" => {
+                let nibble = self.value()? as u16 & 0x0F;
+                let name = self.next()?;
+                let addr = self.label_address(&name, Fixup::Unpack, self.here);
+
+                self.emit_op(0x6000 | (nibble << 4) | (addr >> 8))?;
+                self.emit_op(0x6100 | (addr & 0xFF))?;
+            }
+            ":next" => self.next_label = Some(self.next()?),
+            ":org" => {
+                let addr = self.value()?;
+
+                if addr < DEFAULT_ADDRESS as i64 || addr >= MEM_SIZE as i64 {
+                    return Err(self.error(&format!("Invalid address: {}", addr)));
+                }
+
+                self.here = addr as usize;
+            }
+            ":byte" => {
+                let value = self.value()?;
+                self.emit_byte(value as u8)?;
+            }
+            ":pointer" => {
+                let name = self.next()?;
+                let addr = self.label_address(&name, Fixup::Pointer, self.here);
+
+                self.emit_byte((addr >> 8) as u8)?;
+                self.emit_byte(addr as u8)?;
+            }
+            ":call" => {
+                let addr = self.address()?;
+                self.emit_op(0x2000 | addr)?;
+            }
+            ":macro" => self.define_macro()?,
+            ":calc" => {
+                let name = self.next()?;
+                self.expect("{")?;
+
+                let value = self.calc()?;
+                self.constants.insert(name, value);
+            }
+            ":assert" => {
+                if self.peek().is_some_and(|token| token != "{") {
+                    self.next()?;
+                }
+
+                self.expect("{")?;
+
+                if self.calc()? == 0 {
+                    return Err(self.error("Assertion failed"));
+                }
+            }
+            ":breakpoint" | ":proto" => {
+                self.next()?;
+            }
+            ":monitor" => {
+                self.next()?;
+                self.next()?;
+            }
+            ";" | "return" => self.emit_op(0x00EE)?,
+            "clear" => self.emit_op(0x00E0)?,
+            "exit" => self.emit_op(0x00FD)?,
+            "lores" => self.emit_op(0x00FE)?,
+            "hires" => self.emit_op(0x00FF)?,
+            "scroll-left" => self.emit_op(0x00FC)?,
+            "scroll-right" => self.emit_op(0x00FB)?,
+            "scroll-down" => {
+                let n = self.nibble()?;
+                self.emit_op(0x00C0 | n)?;
+            }
+            "scroll-up" => {
+                let n = self.nibble()?;
+                self.emit_op(0x00D0 | n)?;
+            }
+            "bcd" => self.register_op(0xF033)?,
+            "save" => self.register_op(0xF055)?,
+            "load" => self.register_op(0xF065)?,
+            "saveflags" => self.register_op(0xF075)?,
+            "loadflags" => self.register_op(0xF085)?,
+            "audio" => self.emit_op(0xF002)?,
+            "plane" => {
+                let n = self.nibble()?;
+                self.emit_op(0xF001 | (n << 8))?;
+            }
+            "sprite" => {
+                let x = self.register()? as u16;
+                let y = self.register()? as u16;
+                let n = self.nibble()?;
+                self.emit_op(0xD000 | (x << 8) | (y << 4) | n)?;
+            }
+            "jump" => {
+                let addr = self.address()?;
+                self.emit_op(0x1000 | addr)?;
+            }
+            "jump0" => {
+                let addr = self.address()?;
+                self.emit_op(0xB000 | addr)?;
+            }
+            "native" => {
+                let addr = self.address()?;
+                self.emit_op(addr)?;
+            }
+            "delay" | "buzzer" | "pitch" => {
+                self.expect(":=")?;
+
+                let x = self.register()? as u16;
+                let op = match token.as_str() {
+                    "delay" => 0xF015,
+                    "buzzer" => 0xF018,
+                    _ => 0xF03A,
+                };
+
+                self.emit_op(op | (x << 8))?;
+            }
+            "i" => self.index_statement()?,
+            "if" => self.if_statement()?,
+            "else" => match self.blocks.pop() {
+                Some(Block::If { jump }) => {
+                    let addr = self.emit_jump()?;
+
+                    self.patch_jump(jump, self.here);
+                    self.blocks.push(Block::Else { jump: addr });
+                }
+                _ => return Err(self.error("else without if")),
+            },
+            "end" => match self.blocks.pop() {
+                Some(Block::If { jump } | Block::Else { jump }) => {
+                    self.patch_jump(jump, self.here);
+                }
+                _ => return Err(self.error("end without begin")),
+            },
+            "loop" => self.blocks.push(Block::Loop {
+                start: self.here,
+                breaks: Vec::new(),
+            }),
+            "while" => {
+                let condition = self.condition()?;
+
+                self.emit_setup(&condition)?;
+                self.emit_op(condition.skip_if_true())?;
+
+                let addr = self.emit_jump()?;
+
+                match self.blocks.iter_mut().rev().find_map(|block| match block {
+                    Block::Loop { breaks, .. } => Some(breaks),
+                    _ => None,
+                }) {
+                    Some(breaks) => breaks.push(addr),
+                    None => return Err(self.error("while outside of a loop")),
+                }
+            }
+            "again" => match self.blocks.pop() {
+                Some(Block::Loop { start, breaks }) => {
+                    self.emit_op(0x1000 | start as u16)?;
+
+                    for addr in breaks {
+                        self.patch_jump(addr, self.here);
+                    }
+                }
+                _ => return Err(self.error("again without loop")),
+            },
+            _ if self.parse_register(&token).is_some() => {
+                self.push_front(token);
+                self.assignment()?;
+            }
+            _ if self.macros.contains_key(&token) => self.expand_macro(&token)?,
+            // bare numbers are data while bare labels are subroutine calls
+            _ => match parse_number(&token).or_else(|| self.constants.get(&token).copied()) {
+                Some(value) => self.emit_byte(value as u8)?,
+                None => {
+                    let addr = self.label_address(&token, Fixup::Address, self.here);
+                    self.emit_op(0x2000 | addr)?;
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    fn assignment(&mut self) -> Result<(), Error> {
+        let x = self.register()? as u16;
+        let operator = self.next()?;
+        let rhs = self.next()?;
+        let y = self.parse_register(&rhs).map(|y| y as u16);
+
+        let op = match (operator.as_str(), rhs.as_str(), y) {
+            (":=", "random", _) => {
+                let mask = self.value()? as u16 & 0xFF;
+                0xC000 | (x << 8) | mask
+            }
+            (":=", "key", _) => 0xF00A | (x << 8),
+            (":=", "delay", _) => 0xF007 | (x << 8),
+            (":=", _, Some(y)) => 0x8000 | (x << 8) | (y << 4),
+            (":=", _, None) => 0x6000 | (x << 8) | self.byte(&rhs)?,
+            ("+=", _, Some(y)) => 0x8004 | (x << 8) | (y << 4),
+            ("+=", _, None) => 0x7000 | (x << 8) | self.byte(&rhs)?,
+            ("-=", _, Some(y)) => 0x8005 | (x << 8) | (y << 4),
+            ("-=", _, None) => 0x7000 | (x << 8) | (self.byte(&rhs)?.wrapping_neg() & 0xFF),
+            (operator, _, Some(y)) => {
+                let n = match operator {
+                    "|=" => 0x1,
+                    "&=" => 0x2,
+                    "^=" => 0x3,
+                    ">>=" => 0x6,
+                    "=-" => 0x7,
+                    "<<=" => 0xE,
+                    _ => return Err(self.error(&format!("Unknown operator: {}", operator))),
+                };
+
+                0x8000 | (x << 8) | (y << 4) | n
+            }
+            (operator, _, None) => {
+                let message = format!("Invalid operand for {}: {}", operator, rhs);
+                return Err(self.error(&message));
+            }
+        };
+
+        self.emit_op(op)
+    }
+
+    fn index_statement(&mut self) -> Result<(), Error> {
+        let operator = self.next()?;
+
+        match operator.as_str() {
+            ":=" => match self.peek() {
+                Some("hex") => {
+                    self.next()?;
+                    self.register_op(0xF029)
+                }
+                Some("bighex") => {
+                    self.next()?;
+                    self.register_op(0xF030)
+                }
+                _ => {
+                    let addr = self.address()?;
+                    self.emit_op(0xA000 | addr)
+                }
+            },
+            "+=" => self.register_op(0xF01E),
+            _ => Err(self.error(&format!("Unknown operator: {}", operator))),
+        }
+    }
+
+    fn if_statement(&mut self) -> Result<(), Error> {
+        let condition = self.condition()?;
+        let keyword = self.next()?;
+
+        self.emit_setup(&condition)?;
+
+        match keyword.as_str() {
+            "then" => self.emit_op(condition.skip_if_false),
+            "begin" => {
+                self.emit_op(condition.skip_if_true())?;
+
+                let jump = self.emit_jump()?;
+                self.blocks.push(Block::If { jump });
+
+                Ok(())
+            }
+            _ => Err(self.error(&format!("Expected then or begin: {}", keyword))),
+        }
+    }
+
+    fn condition(&mut self) -> Result<Condition, Error> {
+        let x = self.register()? as u16;
+        let operator = self.next()?;
+
+        if operator == "key" || operator == "-key" {
+            let skip_if_false = match operator.as_str() {
+                "key" => 0xE0A1,
+                _ => 0xE09E,
+            };
+
+            return Ok(Condition {
+                setup: Vec::new(),
+                skip_if_false: skip_if_false | (x << 8),
+            });
+        }
+
+        let rhs = self.next()?;
+        let y = self.parse_register(&rhs).map(|y| y as u16);
+        let load_rhs = match y {
+            Some(y) => 0x8F00 | (y << 4),
+            None => 0x6F00 | self.byte(&rhs)?,
+        };
+
+        let condition = match (operator.as_str(), y) {
+            ("==", Some(y)) => (Vec::new(), 0x9000 | (x << 8) | (y << 4)),
+            ("!=", Some(y)) => (Vec::new(), 0x5000 | (x << 8) | (y << 4)),
+            ("==", None) => (Vec::new(), 0x4000 | (x << 8) | self.byte(&rhs)?),
+            ("!=", None) => (Vec::new(), 0x3000 | (x << 8) | self.byte(&rhs)?),
+            // VF is set to x >= rhs by VF =- VX, and to rhs >= x by VF -= VX
+            (">=", _) => (vec![load_rhs, 0x8F07 | (x << 4)], 0x4F01),
+            ("<", _) => (vec![load_rhs, 0x8F07 | (x << 4)], 0x4F00),
+            ("<=", _) => (vec![load_rhs, 0x8F05 | (x << 4)], 0x4F01),
+            (">", _) => (vec![load_rhs, 0x8F05 | (x << 4)], 0x4F00),
+            _ => return Err(self.error(&format!("Unknown comparison: {}", operator))),
+        };
+
+        Ok(Condition {
+            setup: condition.0,
+            skip_if_false: condition.1,
+        })
+    }
+
+    fn define_macro(&mut self) -> Result<(), Error> {
+        let name = self.next()?;
+        let mut args = Vec::new();
+
+        loop {
+            let token = self.next()?;
+
+            if token == "{" {
+                break;
+            }
+
+            args.push(token);
+        }
+
+        let mut body = Vec::new();
+        let mut depth = 1;
+
+        loop {
+            let token = self
+                .tokens
+                .pop_front()
+                .ok_or_else(|| self.error("Unterminated macro"))?;
+
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" => depth -= 1,
+                _ => {}
+            }
+
+            if depth == 0 {
+                break;
+            }
+
+            body.push(token);
+        }
+
+        self.macros.insert(name, Macro { args, body });
+
+        Ok(())
+    }
+
+    fn expand_macro(&mut self, name: &str) -> Result<(), Error> {
+        if self.depth >= MAX_MACRO_DEPTH {
+            return Err(self.error(&format!("Macro expansion too deep: {}", name)));
+        }
+
+        let depth = self.depth + 1;
+        let count = self.macros[name].args.len();
+        let values = (0..count)
+            .map(|_| self.next())
+            .collect::<Result<Vec<_>, _>>()?;
+        let definition = &self.macros[name];
+
+        for token in definition.body.iter().rev() {
+            let text = match definition.args.iter().position(|arg| *arg == token.text) {
+                Some(index) => values[index].clone(),
+                None => token.text.clone(),
+            };
+
+            self.tokens.push_front(Token {
+                text,
+                line: token.line,
+                depth,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn calc(&mut self) -> Result<i64, Error> {
+        let value = self.calc_expression()?;
+
+        self.expect("}")?;
+        Ok(value)
+    }
+
+    // Operators have no precedence and are evaluated from right to left
+    fn calc_expression(&mut self) -> Result<i64, Error> {
+        let lhs = self.calc_term()?;
+
+        let operator = match self.peek() {
+            Some(
+                operator @ ("+" | "-" | "*" | "/" | "%" | "&" | "|" | "^" | "<<" | ">>" | "min"
+                | "max" | "<" | ">" | "<=" | ">=" | "==" | "!="),
+            ) => operator.to_string(),
+            _ => return Ok(lhs),
+        };
+
+        self.next()?;
+
+        let rhs = self.calc_expression()?;
+        let divisor = |rhs: i64| match rhs {
+            0 => Err(self.error("Division by zero")),
+            rhs => Ok(rhs),
+        };
+
+        Ok(match operator.as_str() {
+            "+" => lhs.wrapping_add(rhs),
+            "-" => lhs.wrapping_sub(rhs),
+            "*" => lhs.wrapping_mul(rhs),
+            "/" => lhs
+                .checked_div(divisor(rhs)?)
+                .ok_or_else(|| self.error("Division overflow"))?,
+            "%" => lhs
+                .checked_rem(divisor(rhs)?)
+                .ok_or_else(|| self.error("Division overflow"))?,
+            "&" => lhs & rhs,
+            "|" => lhs | rhs,
+            "^" => lhs ^ rhs,
+            "<<" => lhs.wrapping_shl(rhs as u32),
+            ">>" => lhs.wrapping_shr(rhs as u32),
+            "min" => lhs.min(rhs),
+            "max" => lhs.max(rhs),
+            "<" => (lhs < rhs) as i64,
+            ">" => (lhs > rhs) as i64,
+            "<=" => (lhs <= rhs) as i64,
+            ">=" => (lhs >= rhs) as i64,
+            "==" => (lhs == rhs) as i64,
+            _ => (lhs != rhs) as i64,
+        })
+    }
+
+    fn calc_term(&mut self) -> Result<i64, Error> {
+        let token = self.next()?;
+
+        match token.as_str() {
+            "(" => {
+                let value = self.calc_expression()?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            "-" => Ok(self.calc_term()?.wrapping_neg()),
+            "~" => Ok(!self.calc_term()?),
+            "!" => Ok((self.calc_term()? == 0) as i64),
+            "@" => {
+                let addr = self.calc_term()?;
+                let byte = self.rom.get(addr as usize).copied();
+
+                byte.map(|byte| byte as i64)
+                    .ok_or_else(|| self.error(&format!("Invalid address: {}", addr)))
+            }
+            "HERE" => Ok(self.here as i64),
+            _ => self
+                .parse_value(&token)
+                .ok_or_else(|| self.error(&format!("Undefined name: {}", token))),
+        }
+    }
+
+    fn define_label(&mut self, name: String, addr: usize) -> Result<(), Error> {
+        if self.labels.contains_key(&name) {
+            return Err(self.error(&format!("Duplicate label: {}", name)));
+        }
+
+        self.labels.insert(name, addr);
+        Ok(())
+    }
+
+    // Known labels are resolved right away, others once the program is parsed
+    fn label_address(&mut self, name: &str, fixup: Fixup, addr: usize) -> u16 {
+        if let Some(value) = self.labels.get(name) {
+            return *value as u16;
+        }
+
+        if let Some(value) = self.constants.get(name) {
+            return *value as u16;
+        }
+
+        self.fixups.push((addr, name.to_string(), fixup, self.line));
+        0
+    }
+
+    fn address(&mut self) -> Result<u16, Error> {
+        let token = self.next()?;
+
+        if let Some(value) = parse_number(&token) {
+            return Ok(value as u16 & 0x0FFF);
+        }
+
+        Ok(self.label_address(&token, Fixup::Address, self.here) & 0x0FFF)
+    }
+
+    fn register_op(&mut self, op: u16) -> Result<(), Error> {
+        let x = self.register()? as u16;
+        self.emit_op(op | (x << 8))
+    }
+
+    fn register(&mut self) -> Result<u8, Error> {
+        let token = self.next()?;
+
+        self.parse_register(&token)
+            .ok_or_else(|| self.error(&format!("Expected a register: {}", token)))
+    }
+
+    fn parse_register(&self, token: &str) -> Option<u8> {
+        if let Some(register) = self.aliases.get(token) {
+            return Some(*register);
+        }
+
+        let digit = token.strip_prefix(['v', 'V'])?;
+
+        match digit.len() {
+            1 => u8::from_str_radix(digit, 16).ok(),
+            _ => None,
+        }
+    }
+
+    fn value(&mut self) -> Result<i64, Error> {
+        let token = self.next()?;
+
+        if token == "{" {
+            return self.calc();
+        }
+
+        self.parse_value(&token)
+            .ok_or_else(|| self.error(&format!("Undefined name: {}", token)))
+    }
+
+    fn parse_value(&self, token: &str) -> Option<i64> {
+        parse_number(token)
+            .or_else(|| self.constants.get(token).copied())
+            .or_else(|| self.labels.get(token).map(|addr| *addr as i64))
+    }
+
+    fn byte(&self, token: &str) -> Result<u16, Error> {
+        match self.parse_value(token) {
+            Some(value) if (-128..=255).contains(&value) => Ok(value as u16 & 0xFF),
+            Some(value) => Err(self.error(&format!("Value out of range: {}", value))),
+            None => Err(self.error(&format!("Undefined name: {}", token))),
+        }
+    }
+
+    fn nibble(&mut self) -> Result<u16, Error> {
+        let value = self.value()?;
+
+        match value {
+            0..=15 => Ok(value as u16),
+            _ => Err(self.error(&format!("Value out of range: {}", value))),
+        }
+    }
+
+    fn emit_setup(&mut self, condition: &Condition) -> Result<(), Error> {
+        for op in &condition.setup {
+            self.emit_op(*op)?;
+        }
+
+        Ok(())
+    }
+
+    fn emit_jump(&mut self) -> Result<usize, Error> {
+        let addr = self.here;
+
+        self.emit_op(0x1000)?;
+        Ok(addr)
+    }
+
+    fn patch_jump(&mut self, addr: usize, target: usize) {
+        self.rom[addr] = 0x10 | (target >> 8) as u8 & 0x0F;
+        self.rom[addr + 1] = target as u8;
+    }
+
+    fn emit_op(&mut self, op: u16) -> Result<(), Error> {
+        if let Some(name) = self.next_label.take() {
+            self.define_label(name, self.here + 1)?;
+        }
+
+        self.emit_byte((op >> 8) as u8)?;
+        self.emit_byte(op as u8)
+    }
+
+    fn emit_byte(&mut self, byte: u8) -> Result<(), Error> {
+        if self.here >= MEM_SIZE {
+            return Err(self.error("Program too large"));
+        }
+
+        self.rom[self.here] = byte;
+        self.here += 1;
+        self.end = self.end.max(self.here);
+
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<String, Error> {
+        let token = self
+            .tokens
+            .pop_front()
+            .ok_or_else(|| self.error("Unexpected end of file"))?;
+
+        self.line = token.line;
+        self.depth = token.depth;
+        Ok(token.text)
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.front().map(|token| token.text.as_str())
+    }
+
+    fn push_front(&mut self, text: String) {
+        self.tokens.push_front(Token {
+            text,
+            line: self.line,
+            depth: self.depth,
+        });
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), Error> {
+        let token = self.next()?;
+
+        if token != expected {
+            return Err(self.error(&format!("Expected {}: {}", expected, token)));
+        }
+
+        Ok(())
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("line {}: {}", self.line, message),
+        )
+    }
+}
+
+fn parse_number(token: &str) -> Option<i64> {
+    let (negative, digits) = match token.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, token),
+    };
+
+    let value = if let Some(hex) = digits.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if let Some(binary) = digits.strip_prefix("0b") {
+        i64::from_str_radix(binary, 2).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+
+    Some(if negative { -value } else { value })
+}
@@ -0,0 +1,63 @@
+pub const DEFAULT_SAMPLE_RATE: u32 = 44100;
+pub const DEFAULT_PITCH: f32 = 440.0;
+pub const DEFAULT_VOLUME: f32 = 0.25;
+
+// Square wave played while the sound timer is active, the phase is kept
+// between buffers so that they join without clicks. It is a fraction of a
+// period, radians would drift from the pitch with rounding errors
+#[derive(Debug)]
+pub struct Beeper {
+    sample_rate: u32,
+    pitch: f32,
+    volume: f32,
+    phase: f32,
+}
+
+impl Default for Beeper {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_RATE)
+    }
+}
+
+impl Beeper {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            pitch: DEFAULT_PITCH,
+            volume: DEFAULT_VOLUME,
+            phase: 0.0,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate.max(1);
+    }
+
+    // Frequency of the tone in Hz
+    pub fn set_pitch(&mut self, pitch: f32) {
+        self.pitch = pitch.clamp(1.0, self.sample_rate as f32 / 2.0);
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    // Samples between -1 and 1, silence restarts the wave
+    pub fn generate(&mut self, buffer: &mut [f32], active: bool) {
+        if !active {
+            buffer.fill(0.0);
+            self.phase = 0.0;
+            return;
+        }
+
+        let step = self.pitch / self.sample_rate as f32;
+
+        for sample in buffer {
+            *sample = match self.phase < 0.5 {
+                true => self.volume,
+                false => -self.volume,
+            };
+            self.phase = (self.phase + step).fract();
+        }
+    }
+}
@@ -0,0 +1,450 @@
+use crate::{
+    config::{self, Settings},
+    renderer::Renderer,
+    save_screenshot,
+    watcher::Watcher,
+};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style,
+    terminal::{self, ClearType},
+    QueueableCommand,
+};
+use std::{
+    fs::File,
+    io::{stdout, BufWriter, Error, Write},
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use yace::{chip::Chip, phosphor::Phosphor, recorder::GifRecorder};
+
+const MAX_STEPS: u32 = 1000;
+const NOTICE_DURATION: Duration = Duration::from_secs(3);
+
+// Why the main loop has returned
+pub enum Exit {
+    Quit,
+    Reload,
+}
+
+#[derive(Debug)]
+enum KeyboardEvent {
+    Press(u8),
+    Release(u8),
+    Pause,
+    FrameAdvance,
+    Step,
+    Reset,
+    SpeedUp,
+    SpeedDown,
+    Screenshot,
+    Exit,
+}
+
+impl KeyboardEvent {
+    fn from_key_event(event: KeyEvent, settings: &Settings) -> Option<Self> {
+        let is_interrupt = matches!(
+            (event.modifiers, event.code),
+            (KeyModifiers::CONTROL, KeyCode::Char('c'))
+        );
+        let hotkeys = &settings.hotkeys;
+
+        if is_interrupt || hotkeys.exit.matches(&event) {
+            return Some(Self::Exit);
+        }
+
+        let actions = [
+            (&hotkeys.pause, Self::Pause),
+            (&hotkeys.frame_advance, Self::FrameAdvance),
+            (&hotkeys.step, Self::Step),
+            (&hotkeys.reset, Self::Reset),
+            (&hotkeys.speed_up, Self::SpeedUp),
+            (&hotkeys.speed_down, Self::SpeedDown),
+            (&hotkeys.screenshot, Self::Screenshot),
+        ];
+
+        for (hotkey, action) in actions {
+            if hotkey.matches(&event) {
+                // hotkeys are triggered once even if release events are reported
+                return (event.kind != KeyEventKind::Release).then_some(action);
+            }
+        }
+
+        let key = config::get_key_name(event.code).and_then(|name| settings.keymap.get_key(&name));
+
+        key.map(|key| match event.kind {
+            KeyEventKind::Release => Self::Release(key),
+            _ => Self::Press(key),
+        })
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    frames: u32,
+    instructions: u32,
+    fps: u32,
+    ips: u32,
+    since: Option<Instant>,
+}
+
+impl Stats {
+    // Returns true when the displayed values have been refreshed
+    fn update(&mut self, now: Instant) -> bool {
+        let since = *self.since.get_or_insert(now);
+        let elapsed = now.duration_since(since);
+
+        if elapsed < Duration::from_secs(1) {
+            return false;
+        }
+
+        self.fps = (self.frames as f64 / elapsed.as_secs_f64()).round() as u32;
+        self.ips = (self.instructions as f64 / elapsed.as_secs_f64()).round() as u32;
+        self.frames = 0;
+        self.instructions = 0;
+        self.since = Some(now);
+
+        true
+    }
+}
+
+pub struct App {
+    settings: Settings,
+    name: String,
+    hints: String,
+    chip: Chip,
+    renderer: Renderer,
+    phosphor: Option<Phosphor>,
+    recorder: Option<GifRecorder<BufWriter<File>>>,
+    steps: u32,
+    frame_duration: Duration,
+    paused: bool,
+    redraw: bool,
+    stats: Stats,
+    notice: Option<(String, Instant)>,
+}
+
+impl App {
+    pub fn new(settings: Settings, name: String, program: &[u8]) -> Result<Self, Error> {
+        let renderer = create_renderer(&settings);
+        let frame_duration = get_frame_duration(&settings);
+        let mut chip = Chip::default();
+
+        chip.load_at(program, settings.address)?;
+        chip.quirks = settings.quirks;
+
+        Ok(Self {
+            hints: get_hints(&settings),
+            name,
+            chip,
+            renderer,
+            phosphor: settings.persistence.map(Phosphor::new),
+            recorder: None,
+            steps: settings.steps as u32,
+            frame_duration,
+            paused: false,
+            redraw: true,
+            stats: Stats::default(),
+            notice: None,
+            settings,
+        })
+    }
+
+    pub fn get_settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    // Loads a new build of the ROM, the keymap and the speed are kept on demand
+    pub fn reload(
+        &mut self,
+        mut settings: Settings,
+        name: String,
+        program: &[u8],
+        keep_settings: bool,
+    ) -> Result<(), Error> {
+        let renderer = create_renderer(&settings);
+        let (width, height) = renderer.required_size();
+        let (columns, rows) = terminal::size()?;
+
+        // a new renderer can need more room, one more line for the status
+        if (columns as usize) < width || (rows as usize) < height + 1 {
+            let message = format!("The required terminal size is {}x{}", width, height + 1);
+            return Err(Error::other(message));
+        }
+
+        self.chip.load_at(program, settings.address)?;
+        self.chip.quirks = settings.quirks;
+        self.chip.power_cycle();
+
+        if keep_settings {
+            settings.keymap = self.settings.keymap.clone();
+        } else {
+            self.steps = settings.steps as u32;
+            self.frame_duration = get_frame_duration(&settings);
+        }
+
+        // the recorder keeps going, its frames do not depend on the settings
+        self.renderer = renderer;
+        self.phosphor = settings.persistence.map(Phosphor::new);
+        self.redraw = true;
+        self.hints = get_hints(&settings);
+        self.name = name;
+        self.settings = settings;
+        self.set_notice("RELOADED".to_string());
+
+        Ok(())
+    }
+
+    pub fn set_notice(&mut self, notice: String) {
+        self.notice = Some((notice, Instant::now()));
+        self.redraw = true;
+    }
+
+    pub fn set_recorder(&mut self, recorder: GifRecorder<BufWriter<File>>) {
+        self.recorder = Some(recorder);
+    }
+
+    pub fn get_required_size(&self) -> (usize, usize) {
+        let (width, height) = self.renderer.required_size();
+
+        // one more line for the status
+        (width, height + 1)
+    }
+
+    pub fn get_display_buffer(&self) -> &[u8] {
+        self.chip.display.get_buffer()
+    }
+
+    pub fn run(&mut self, mut watcher: Option<&mut Watcher>) -> Result<Exit, Error> {
+        let mut next_frame = Instant::now();
+
+        loop {
+            if !self.paused {
+                self.run_frame()?;
+            }
+
+            if self.stats.update(Instant::now()) {
+                self.redraw = true;
+            }
+
+            if self.redraw {
+                self.draw()?;
+            }
+
+            if watcher.as_deref_mut().is_some_and(Watcher::poll) {
+                return Ok(Exit::Reload);
+            }
+
+            next_frame += self.frame_duration;
+
+            // late frames are not caught up to avoid bursts
+            if next_frame < Instant::now() {
+                next_frame = Instant::now();
+            }
+
+            // input is still read when the frames take longer than planned
+            loop {
+                let timeout = next_frame.saturating_duration_since(Instant::now());
+
+                if !event::poll(timeout)? {
+                    break;
+                }
+
+                let Event::Key(event) = event::read()? else {
+                    continue;
+                };
+
+                match KeyboardEvent::from_key_event(event, &self.settings) {
+                    Some(KeyboardEvent::Exit) => return Ok(Exit::Quit),
+                    Some(event) => self.handle_event(event)?,
+                    None => {}
+                }
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<(), Error> {
+        if let Some(recorder) = self.recorder {
+            recorder.finish()?.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: KeyboardEvent) -> Result<(), Error> {
+        match event {
+            KeyboardEvent::Press(key) => self.chip.keyboard.set_key(key),
+            KeyboardEvent::Release(key) => self.chip.keyboard.unset_key(key),
+            KeyboardEvent::Pause => {
+                self.paused = !self.paused;
+                self.redraw = true;
+            }
+            KeyboardEvent::FrameAdvance => {
+                self.paused = true;
+                self.run_frame()?;
+                self.redraw = true;
+            }
+            KeyboardEvent::Step => {
+                self.paused = true;
+                self.step()?;
+                self.redraw = true;
+            }
+            KeyboardEvent::Reset => {
+                self.reset();
+            }
+            KeyboardEvent::SpeedUp => {
+                self.steps = (self.steps + self.get_speed_delta()).min(MAX_STEPS);
+                self.redraw = true;
+            }
+            KeyboardEvent::SpeedDown => {
+                self.steps = self.steps.saturating_sub(self.get_speed_delta()).max(1);
+                self.redraw = true;
+            }
+            KeyboardEvent::Screenshot => {
+                let path = get_screenshot_path();
+
+                // a failed screenshot should not end the game
+                match save_screenshot(&self.settings, self.get_display_buffer(), &path) {
+                    Ok(()) => self.set_notice(format!("SCREENSHOT SAVED: {}", path.display())),
+                    Err(error) => self.set_notice(format!("SCREENSHOT FAILED: {}", error)),
+                }
+            }
+            KeyboardEvent::Exit => {}
+        }
+
+        Ok(())
+    }
+
+    fn run_frame(&mut self) -> Result<(), Error> {
+        for _ in 0..self.steps {
+            self.step()?;
+        }
+
+        self.chip.update_timers();
+        self.stats.frames += 1;
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.add_frame(self.chip.display.get_buffer())?;
+        }
+
+        if let Some(phosphor) = &mut self.phosphor {
+            phosphor.update(&self.chip.display);
+            self.redraw = true;
+        }
+
+        Ok(())
+    }
+
+    fn step(&mut self) -> Result<(), Error> {
+        self.chip.tick()?;
+        self.stats.instructions += 1;
+
+        if self.chip.display.has_changed() {
+            self.redraw = true;
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.chip.reset();
+
+        if let Some(phosphor) = &mut self.phosphor {
+            phosphor.clear();
+        }
+
+        self.redraw = true;
+    }
+
+    fn get_speed_delta(&self) -> u32 {
+        (self.steps / 10).max(1)
+    }
+
+    fn get_status(&self) -> String {
+        let mut status = self.name.clone();
+
+        // notices come first so that they are not truncated
+        if let Some((notice, since)) = &self.notice {
+            if since.elapsed() < NOTICE_DURATION {
+                status.push_str(&format!(" | {}", notice));
+            }
+        }
+
+        if !self.hints.is_empty() {
+            status.push_str(&format!(" | {}", self.hints));
+        }
+
+        status.push_str(&format!(
+            " | {} FPS | {} IPS | {} IPF",
+            self.stats.fps, self.stats.ips, self.steps
+        ));
+
+        if self.paused {
+            status.push_str(" | PAUSED");
+        }
+
+        status
+    }
+
+    fn draw(&mut self) -> Result<(), Error> {
+        let frame = match &self.phosphor {
+            Some(phosphor) => self.renderer.render(phosphor.get_buffer(), true),
+            None => self.renderer.render(self.chip.display.get_buffer(), false),
+        };
+        let (width, height) = self.renderer.required_size();
+        let status = self.get_status().chars().take(width).collect::<String>();
+
+        self.redraw = false;
+
+        stdout()
+            .queue(terminal::Clear(ClearType::All))?
+            .queue(cursor::MoveTo(0, 0))?
+            .queue(style::Print(frame))?
+            .queue(style::ResetColor)?
+            .queue(cursor::MoveTo(0, height as u16))?
+            .queue(style::Print(status))?
+            .flush()
+    }
+}
+
+// Names are unique even for several screenshots in the same millisecond
+fn get_screenshot_path() -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+
+    (0..)
+        .map(|index| match index {
+            0 => PathBuf::from(format!("yace-{}.png", timestamp)),
+            _ => PathBuf::from(format!("yace-{}-{}.png", timestamp, index)),
+        })
+        .find(|path| !path.exists())
+        .unwrap_or_default()
+}
+
+fn create_renderer(settings: &Settings) -> Renderer {
+    Renderer::new(
+        settings.renderer,
+        settings.pixel,
+        settings.fg.to_color(),
+        settings.bg.to_color(),
+    )
+}
+
+// The clock speed and the steps per cycle define the frame rate
+fn get_frame_duration(settings: &Settings) -> Duration {
+    Duration::from_secs_f64(settings.steps as f64 / settings.clock as f64)
+}
+
+// Key hints from the ROM database, like "up:1 down:q"
+fn get_hints(settings: &Settings) -> String {
+    settings
+        .info
+        .iter()
+        .flat_map(|info| &info.keys)
+        .map(|(hint, key)| format!("{}:{}", hint, settings.keymap.get_name(*key)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
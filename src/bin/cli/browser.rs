@@ -0,0 +1,258 @@
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    style::{self, Stylize},
+    terminal::{self, ClearType},
+    QueueableCommand,
+};
+use std::{
+    fs::{self, File},
+    io::{stdout, BufReader, Error, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+use yace::{database, rom::get_max_size, rom::DEFAULT_ADDRESS};
+use zip::ZipArchive;
+
+pub const MIN_SIZE: (usize, usize) = (40, 5);
+
+const EXTENSIONS: [&str; 8] = ["ch8", "c8", "sc8", "xo8", "rom", "bin", "8o", "gif"];
+// Octo sources and cartridges are not limited by the memory size
+const SOURCE_EXTENSIONS: [&str; 2] = ["8o", "gif"];
+const MAX_SOURCE_SIZE: u64 = 1 << 20;
+
+pub struct Entry {
+    pub path: PathBuf,
+    pub title: Option<String>,
+    pub content: Vec<u8>,
+}
+
+impl Entry {
+    fn new(path: PathBuf, content: Vec<u8>) -> Self {
+        let title = database::lookup_program(&content).map(|info| info.title);
+
+        Self {
+            path,
+            title,
+            content,
+        }
+    }
+
+    fn label(&self) -> String {
+        match &self.title {
+            Some(title) => format!("{} ({})", title, self.path.display()),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+// Directories and zip archives are browsed instead of being loaded
+pub fn is_collection(path: &Path) -> bool {
+    path.is_dir() || has_extension(path, "zip")
+}
+
+pub fn read_collection(path: &Path) -> Result<Vec<Entry>, Error> {
+    let mut entries = match path.is_dir() {
+        true => read_directory(path, path)?,
+        false => read_archive(path)?,
+    };
+
+    if entries.is_empty() {
+        let message = format!("No ROM found in {}", path.display());
+        return Err(Error::new(ErrorKind::NotFound, message));
+    }
+
+    entries.sort_by_key(|entry| entry.label().to_lowercase());
+
+    Ok(entries)
+}
+
+// Linked directories are not followed to avoid loops, unreadable entries
+// are left out
+fn read_directory(root: &Path, path: &Path) -> Result<Vec<Entry>, Error> {
+    let mut entries = Vec::new();
+
+    for item in fs::read_dir(path)?.flatten() {
+        let item_path = item.path();
+        let Ok(file_type) = item.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            entries.extend(read_directory(root, &item_path).unwrap_or_default());
+        } else if (file_type.is_file() || item_path.is_file()) && is_rom(&item_path) {
+            let Ok(Some(content)) =
+                File::open(&item_path).and_then(|file| read_limited(file, &item_path))
+            else {
+                continue;
+            };
+            let name = item_path.strip_prefix(root).unwrap_or(&item_path);
+
+            entries.push(Entry::new(name.to_path_buf(), content));
+        }
+    }
+
+    Ok(entries)
+}
+
+fn read_archive(path: &Path) -> Result<Vec<Entry>, Error> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(path)?))
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    let mut entries = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+
+        let Some(name) = file.enclosed_name().filter(|name| is_rom(name)) else {
+            continue;
+        };
+
+        if !file.is_file() || file.size() > get_size_limit(&name) {
+            continue;
+        }
+
+        if let Some(content) = read_limited(&mut file, &name)? {
+            entries.push(Entry::new(name, content));
+        }
+    }
+
+    Ok(entries)
+}
+
+// Files without extension are common in ROM packs
+fn is_rom(path: &Path) -> bool {
+    let is_hidden = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    let is_known = EXTENSIONS
+        .iter()
+        .any(|extension| has_extension(path, extension));
+
+    !is_hidden && (is_known || path.extension().is_none())
+}
+
+// Raw binaries have to fit in memory
+fn get_size_limit(path: &Path) -> u64 {
+    match SOURCE_EXTENSIONS
+        .iter()
+        .any(|extension| has_extension(path, extension))
+    {
+        true => MAX_SOURCE_SIZE,
+        false => get_max_size(DEFAULT_ADDRESS) as u64,
+    }
+}
+
+// Sizes reported by the file system or the archive are not trusted, files
+// above the limit are skipped without being read entirely
+fn read_limited<R: Read>(reader: R, path: &Path) -> Result<Option<Vec<u8>>, Error> {
+    let limit = get_size_limit(path);
+    let mut content = Vec::new();
+
+    reader.take(limit + 1).read_to_end(&mut content)?;
+
+    Ok((content.len() as u64 <= limit).then_some(content))
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|value| value.eq_ignore_ascii_case(extension))
+}
+
+pub struct Browser<'a> {
+    entries: &'a [Entry],
+    selected: usize,
+    offset: usize,
+}
+
+impl<'a> Browser<'a> {
+    pub fn new(entries: &'a [Entry]) -> Self {
+        Self {
+            entries,
+            selected: 0,
+            offset: 0,
+        }
+    }
+
+    // Returns None when the menu is closed without choosing a ROM
+    pub fn select(&mut self) -> Result<Option<&'a Entry>, Error> {
+        loop {
+            self.draw()?;
+
+            let Event::Key(event) = event::read()? else {
+                continue;
+            };
+
+            if event.kind == KeyEventKind::Release {
+                continue;
+            }
+
+            let page = self.get_page_size();
+            let last = self.entries.len() - 1;
+
+            match event.code {
+                KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(last),
+                KeyCode::PageUp => self.selected = self.selected.saturating_sub(page),
+                KeyCode::PageDown => self.selected = (self.selected + page).min(last),
+                KeyCode::Home => self.selected = 0,
+                KeyCode::End => self.selected = last,
+                KeyCode::Enter => return Ok(Some(&self.entries[self.selected])),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char('c') if event.modifiers.contains(event::KeyModifiers::CONTROL) => {
+                    return Ok(None)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_page_size(&self) -> usize {
+        let (_, height) = terminal::size().unwrap_or((0, MIN_SIZE.1 as u16));
+
+        // one line for the header and one for the help
+        (height as usize).saturating_sub(2).max(1)
+    }
+
+    fn draw(&mut self) -> Result<(), Error> {
+        let (width, _) = terminal::size()?;
+        let page = self.get_page_size();
+        let truncate = |text: String| text.chars().take(width as usize).collect::<String>();
+
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + page {
+            self.offset = self.selected + 1 - page;
+        }
+
+        let mut stdout = stdout();
+
+        stdout
+            .queue(terminal::Clear(ClearType::All))?
+            .queue(cursor::MoveTo(0, 0))?
+            .queue(style::Print(truncate(format!(
+                "Select a ROM ({} found)",
+                self.entries.len()
+            ))))?;
+
+        let visible = self.entries.iter().enumerate().skip(self.offset).take(page);
+
+        for (row, (index, entry)) in visible.enumerate() {
+            let label = truncate(format!(" {}", entry.label()));
+
+            stdout.queue(cursor::MoveTo(0, row as u16 + 1))?;
+
+            match index == self.selected {
+                true => stdout.queue(style::PrintStyledContent(label.reverse()))?,
+                false => stdout.queue(style::Print(label))?,
+            };
+        }
+
+        stdout
+            .queue(cursor::MoveTo(0, page as u16 + 1))?
+            .queue(style::Print(truncate(
+                "↑/↓ move, Enter play, Esc quit".to_string(),
+            )))?
+            .flush()
+    }
+}
@@ -0,0 +1,187 @@
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+use yace::palette::Rgb;
+
+// Default xterm values of the 16 standard colors
+const ANSI_COLORS: [u32; 16] = [
+    0x000000, 0xCD0000, 0x00CD00, 0xCDCD00, 0x0000EE, 0xCD00CD, 0x00CDCD, 0xE5E5E5, 0x7F7F7F,
+    0xFF0000, 0x00FF00, 0xFFFF00, 0x5C5CFF, 0xFF00FF, 0x00FFFF, 0xFFFFFF,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedColor {
+    Green,
+    Red,
+    Blue,
+    Cyan,
+    Yellow,
+    Purple,
+    Grey,
+    Black,
+    White,
+}
+
+impl NamedColor {
+    const ALL: [NamedColor; 9] = [
+        NamedColor::Green,
+        NamedColor::Red,
+        NamedColor::Blue,
+        NamedColor::Cyan,
+        NamedColor::Yellow,
+        NamedColor::Purple,
+        NamedColor::Grey,
+        NamedColor::Black,
+        NamedColor::White,
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        // "yelllow" was accepted by older versions
+        if name.eq_ignore_ascii_case("yelllow") {
+            return Some(NamedColor::Yellow);
+        }
+
+        Self::ALL
+            .into_iter()
+            .find(|color| color.name().eq_ignore_ascii_case(name))
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            NamedColor::Green => "green",
+            NamedColor::Red => "red",
+            NamedColor::Blue => "blue",
+            NamedColor::Cyan => "cyan",
+            NamedColor::Yellow => "yellow",
+            NamedColor::Purple => "purple",
+            NamedColor::Grey => "grey",
+            NamedColor::Black => "black",
+            NamedColor::White => "white",
+        }
+    }
+
+    fn to_color(self) -> Color {
+        match self {
+            NamedColor::Green => Color::Green,
+            NamedColor::Red => Color::Red,
+            NamedColor::Blue => Color::Blue,
+            NamedColor::Cyan => Color::Cyan,
+            NamedColor::Yellow => Color::Yellow,
+            NamedColor::Purple => Color::Magenta,
+            NamedColor::Black => Color::Black,
+            NamedColor::Grey => Color::Grey,
+            NamedColor::White => Color::White,
+        }
+    }
+
+    fn to_index(self) -> u8 {
+        match self {
+            NamedColor::Green => 10,
+            NamedColor::Red => 9,
+            NamedColor::Blue => 12,
+            NamedColor::Cyan => 14,
+            NamedColor::Yellow => 11,
+            NamedColor::Purple => 13,
+            NamedColor::Grey => 7,
+            NamedColor::Black => 0,
+            NamedColor::White => 15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PixelColor {
+    Named(NamedColor),
+    Indexed(u8),
+    Rgb(Rgb),
+}
+
+impl PixelColor {
+    pub fn to_color(&self) -> Color {
+        match self {
+            PixelColor::Named(color) => color.to_color(),
+            PixelColor::Indexed(index) => Color::AnsiValue(*index),
+            PixelColor::Rgb(color) => Color::Rgb {
+                r: color.r,
+                g: color.g,
+                b: color.b,
+            },
+        }
+    }
+
+    pub fn to_rgb(&self) -> Rgb {
+        match self {
+            PixelColor::Named(color) => get_indexed_rgb(color.to_index()),
+            PixelColor::Indexed(index) => get_indexed_rgb(*index),
+            PixelColor::Rgb(color) => *color,
+        }
+    }
+}
+
+impl FromStr for PixelColor {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.starts_with('#') {
+            return Rgb::from_hex(value)
+                .map(PixelColor::Rgb)
+                .ok_or_else(|| format!("Invalid hex color: {}", value));
+        }
+
+        if let Ok(index) = value.parse::<u8>() {
+            return Ok(PixelColor::Indexed(index));
+        }
+
+        NamedColor::from_name(value)
+            .map(PixelColor::Named)
+            .ok_or_else(|| format!("Unknown color: {}", value))
+    }
+}
+
+impl TryFrom<String> for PixelColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PixelColor> for String {
+    fn from(value: PixelColor) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for PixelColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PixelColor::Named(color) => write!(f, "{}", color.name()),
+            PixelColor::Indexed(index) => write!(f, "{}", index),
+            PixelColor::Rgb(color) => write!(f, "{}", color.to_hex()),
+        }
+    }
+}
+
+// Converts a color from the xterm 256-color palette
+fn get_indexed_rgb(index: u8) -> Rgb {
+    match index {
+        0..=15 => {
+            let [_, r, g, b] = ANSI_COLORS[index as usize].to_be_bytes();
+            Rgb::new(r, g, b)
+        }
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+
+            Rgb::new(level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let value = 8 + (index - 232) * 10;
+            Rgb::new(value, value, value)
+        }
+    }
+}
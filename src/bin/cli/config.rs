@@ -0,0 +1,781 @@
+use crate::{
+    color::{NamedColor, PixelColor},
+    renderer::RendererKind,
+};
+use clap::Args;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    env,
+    fmt::{self, Display},
+    fs,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+use yace::{
+    assembler,
+    database::{self, RomInfo},
+    keymap::{self, Keymap, Preset},
+    octo::{Cartridge, OctoOptions},
+    palette::{Palette, Rgb, Theme},
+    platform::Platform,
+    quirks::Quirks,
+    rom::{self, DEFAULT_ADDRESS},
+    share::Share,
+};
+
+// Options that can be set from the command line, the config file and
+// overridden for each ROM in the config file
+#[derive(Args, Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    /// Symbol used for pixels [default: █]
+    #[arg(short, long, global = true)]
+    pub pixel: Option<char>,
+
+    /// Foreground color: a name, #RRGGBB or a 256-color index [default: green]
+    #[arg(short, long, global = true)]
+    pub fg: Option<PixelColor>,
+
+    /// Background color: a name, #RRGGBB or a 256-color index [default: black]
+    #[arg(short, long, global = true)]
+    pub bg: Option<PixelColor>,
+
+    /// Color theme: amber, green-phosphor, gameboy, high-contrast or octo
+    #[arg(short, long, global = true)]
+    pub theme: Option<String>,
+
+    /// Clock speed [default: 500]
+    #[arg(short, long, global = true)]
+    pub clock: Option<u64>,
+
+    /// Steps per cycle [default: 10]
+    #[arg(short, long, global = true)]
+    pub steps: Option<u16>,
+
+    /// Enable phosphor persistence with the given decay rate per frame, 0 disables it
+    #[arg(long, value_name = "DECAY", num_args = 0..=1, default_missing_value = "64", global = true)]
+    pub persistence: Option<u8>,
+
+    /// Terminal renderer [default: full]
+    #[arg(short, long, global = true)]
+    pub renderer: Option<RendererKind>,
+
+    /// Scale factor used for screenshots and recordings [default: 8]
+    #[arg(long, global = true)]
+    pub scale: Option<usize>,
+
+    /// ROM load address, 0x600 for ETI-660 programs [default: 0x200]
+    #[arg(long, value_parser = parse_address, global = true)]
+    pub address: Option<u16>,
+
+    #[arg(skip)]
+    pub quirks: QuirksConfig,
+
+    #[arg(skip)]
+    pub keymap: KeymapConfig,
+
+    #[arg(skip)]
+    pub hotkeys: HotkeyConfig,
+}
+
+impl Options {
+    fn from_platform(platform: Platform) -> Self {
+        Self {
+            quirks: QuirksConfig::from_quirks(platform.quirks()),
+            ..Default::default()
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(name) = &self.theme {
+            Theme::from_name(name)
+                .ok_or_else(|| invalid_data(format!("Unknown theme: {}", name)))?;
+        }
+
+        self.keymap.validate()
+    }
+
+    fn get_theme_color(&self, color: fn(&Palette) -> Rgb) -> Option<PixelColor> {
+        let theme = Theme::from_name(self.theme.as_deref()?)?;
+
+        Some(PixelColor::Rgb(color(&theme.palette())))
+    }
+
+    fn from_octo(options: &OctoOptions) -> Self {
+        let color = |color: &Option<String>| {
+            color
+                .as_deref()
+                .and_then(Rgb::from_hex)
+                .map(PixelColor::Rgb)
+        };
+
+        let (clock, steps) = options.tickrate.map(from_tickrate).unzip();
+
+        Self {
+            bg: color(&options.background_color),
+            fg: color(&options.fill_color),
+            clock,
+            steps,
+            quirks: QuirksConfig {
+                vf_reset: options.logic_quirks,
+                memory: options.load_store_quirks.map(|quirk| !quirk),
+                shifting: options.shift_quirks,
+                jumping: options.jump_quirks,
+                clipping: options.clip_quirks,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn from_share(share: &Share) -> Self {
+        let color = |color: Rgb| Some(PixelColor::Rgb(color));
+
+        let (clock, steps) = share
+            .tickrate
+            .map(|tickrate| from_tickrate(tickrate as u32))
+            .unzip();
+
+        Self {
+            bg: share.palette.and_then(|palette| color(palette.bg())),
+            fg: share.palette.and_then(|palette| color(palette.fg())),
+            clock,
+            steps,
+            address: Some(share.address),
+            quirks: QuirksConfig::from_quirks(share.quirks),
+            ..Default::default()
+        }
+    }
+
+    fn from_info(info: &RomInfo) -> Self {
+        let color = |index: usize| info.colors.get(index).map(|&color| PixelColor::Rgb(color));
+
+        let (clock, steps) = from_tickrate(info.tickrate);
+
+        Self {
+            bg: color(0),
+            fg: color(1),
+            clock: Some(clock),
+            steps: Some(steps),
+            address: Some(info.address),
+            quirks: QuirksConfig::from_quirks(info.quirks),
+            ..Default::default()
+        }
+    }
+}
+
+// Tickrates are instructions per 60 Hz frame, the clock follows the steps
+// so that clamping them keeps the frame rate
+fn from_tickrate(tickrate: u32) -> (u64, u16) {
+    let steps = tickrate.clamp(1, u16::MAX as u32) as u16;
+
+    (steps as u64 * 60, steps)
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuirksConfig {
+    pub vf_reset: Option<bool>,
+    pub memory: Option<bool>,
+    pub shifting: Option<bool>,
+    pub jumping: Option<bool>,
+    pub clipping: Option<bool>,
+}
+
+impl QuirksConfig {
+    fn from_quirks(quirks: Quirks) -> Self {
+        Self {
+            vf_reset: Some(quirks.vf_reset),
+            memory: Some(quirks.memory),
+            shifting: Some(quirks.shifting),
+            jumping: Some(quirks.jumping),
+            clipping: Some(quirks.clipping),
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeymapConfig {
+    /// Base layout: qwerty, azerty, dvorak or numpad
+    pub preset: Option<String>,
+    /// CHIP-8 key (hex digit) to keyboard key overrides
+    pub keys: HashMap<String, String>,
+}
+
+impl KeymapConfig {
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(name) = &self.preset {
+            Preset::from_name(name)
+                .ok_or_else(|| invalid_data(format!("Unknown keymap preset: {}", name)))?;
+        }
+
+        let mut digits = self.keys.keys().collect::<Vec<_>>();
+        let mut seen = HashMap::new();
+
+        // "0x1" and "1" are the same key, the binding used would be random
+        digits.sort();
+
+        for digit in digits {
+            if let Some(other) = seen.insert(parse_key(digit)?, digit) {
+                let message = format!("CHIP-8 key set twice as {} and {}", other, digit);
+                return Err(invalid_data(message));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_key(&self, key: u8) -> Option<String> {
+        self.keys
+            .iter()
+            .find(|(digit, _)| parse_key(digit).ok() == Some(key))
+            .map(|(_, name)| name.clone())
+    }
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HotkeyConfig {
+    pub exit: Option<String>,
+    pub screenshot: Option<String>,
+    pub pause: Option<String>,
+    pub frame_advance: Option<String>,
+    pub step: Option<String>,
+    pub reset: Option<String>,
+    pub speed_up: Option<String>,
+    pub speed_down: Option<String>,
+}
+
+#[derive(Default)]
+pub struct Config {
+    pub path: Option<PathBuf>,
+    pub options: Options,
+    // Overrides keyed by the SHA-1 of the ROM
+    pub roms: HashMap<String, Options>,
+}
+
+impl Config {
+    // The default location is optional but an explicit path must exist
+    pub fn load(path: Option<&Path>) -> Result<Self, Error> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match get_default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+
+        let content = fs::read_to_string(&path)?;
+        let invalid_file = |error: toml::de::Error| {
+            invalid_data(format!("Invalid config file {}: {}", path.display(), error))
+        };
+
+        let mut table = toml::from_str::<toml::Table>(&content).map_err(invalid_file)?;
+        let roms = match table.remove("roms") {
+            Some(roms) => roms.try_into().map_err(invalid_file)?,
+            None => HashMap::new(),
+        };
+        let options = table.try_into().map_err(invalid_file)?;
+
+        Ok(Self {
+            path: Some(path),
+            options,
+            roms,
+        })
+    }
+
+    pub fn get_rom_options(&self, hash: &str) -> Option<&Options> {
+        self.roms
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(hash))
+            .map(|(_, options)| options)
+    }
+
+    // Command line options take precedence over the ROM overrides, then over
+    // the values from a shared URL, the ROM database, the ROM file format and
+    // the global options of the config file
+    pub fn resolve(&self, cli: &Options, rom: Option<&RomFile>) -> Result<Settings, Error> {
+        let hash = rom.map(|rom| rom.hash.as_str());
+        let info = hash.and_then(database::lookup);
+        let info_options = info.as_ref().map(Options::from_info);
+        let mut layers = vec![(Source::ConfigFile, &self.options)];
+
+        if let Some(rom) = rom.filter(|rom| rom.source == Source::File) {
+            layers.push((Source::File, &rom.options));
+        }
+
+        if let Some(options) = &info_options {
+            layers.push((Source::Database, options));
+        }
+
+        // the settings of a shared URL were picked on purpose
+        if let Some(rom) = rom.filter(|rom| rom.source == Source::Url) {
+            layers.push((Source::Url, &rom.options));
+        }
+
+        if let Some(options) = hash.and_then(|hash| self.get_rom_options(hash)) {
+            layers.push((Source::Rom, options));
+        }
+
+        layers.push((Source::CommandLine, cli));
+
+        for (_, options) in &layers {
+            options.validate()?;
+        }
+
+        let mut resolver = Resolver {
+            layers,
+            entries: Vec::new(),
+            last_source: Source::Default,
+        };
+
+        let pixel = resolver.get("pixel", |o| o.pixel, '█');
+        let theme_name = resolver.get("theme", |o| o.theme.clone().map(Some), None);
+        let theme = theme_name.as_deref().and_then(Theme::from_name);
+        // a theme sets the colors at the level it was given, below the
+        // colors given along with it
+        let fg = resolver.get(
+            "fg",
+            |o| o.fg.clone().or_else(|| o.get_theme_color(Palette::fg)),
+            PixelColor::Named(NamedColor::Green),
+        );
+        let bg = resolver.get(
+            "bg",
+            |o| o.bg.clone().or_else(|| o.get_theme_color(Palette::bg)),
+            PixelColor::Named(NamedColor::Black),
+        );
+        // values are clamped before being listed so that the printed
+        // configuration is the one in effect
+        let clock = resolver.get("clock", |o| o.clock.map(|clock| clock.max(1)), 500);
+        let steps = resolver.get("steps", |o| o.steps.map(|steps| steps.max(1)), 10);
+        let persistence = resolver.get("persistence", |o| o.persistence, 0);
+        let renderer = resolver.get("renderer", |o| o.renderer, RendererKind::Full);
+        let scale = resolver.get("scale", |o| o.scale.map(|scale| scale.max(1)), 8);
+        let address = resolver.get("address", |o| o.address, DEFAULT_ADDRESS);
+
+        if rom.is_some_and(|rom| rom.assembled) && address != DEFAULT_ADDRESS {
+            let message = format!(
+                "Octo programs are assembled for 0x{:03X}, they cannot be loaded at 0x{:03X}",
+                DEFAULT_ADDRESS, address
+            );
+            return Err(invalid_data(message));
+        }
+
+        let default_quirks = Quirks::default();
+        let quirks = Quirks {
+            vf_reset: resolver.get(
+                "quirks.vf_reset",
+                |o| o.quirks.vf_reset,
+                default_quirks.vf_reset,
+            ),
+            memory: resolver.get("quirks.memory", |o| o.quirks.memory, default_quirks.memory),
+            shifting: resolver.get(
+                "quirks.shifting",
+                |o| o.quirks.shifting,
+                default_quirks.shifting,
+            ),
+            jumping: resolver.get(
+                "quirks.jumping",
+                |o| o.quirks.jumping,
+                default_quirks.jumping,
+            ),
+            clipping: resolver.get(
+                "quirks.clipping",
+                |o| o.quirks.clipping,
+                default_quirks.clipping,
+            ),
+        };
+
+        let preset_name = resolver.get(
+            "keymap.preset",
+            |o| o.keymap.preset.clone(),
+            Preset::default().name().to_string(),
+        );
+        let preset_source = resolver.last_source();
+        let preset = Preset::from_name(&preset_name).unwrap_or_default();
+        let mut keymap = Keymap::from_preset(preset);
+
+        for key in 0..16 {
+            let default = (keymap.get_name(key).to_string(), preset_source);
+            let name = resolver.get_or(
+                &format!("keymap.keys.0x{:X}", key),
+                |o| o.keymap.get_key(key),
+                default,
+            );
+
+            keymap.set_key(key, &name);
+        }
+
+        // a physical key can only press one CHIP-8 key
+        for key in 0..16 {
+            let name = keymap.get_name(key);
+
+            if let Some(other) = (key + 1..16).find(|&other| keymap.get_name(other) == name) {
+                let message = format!(
+                    "Key {} is bound to both 0x{:X} and 0x{:X}",
+                    name, key, other
+                );
+                return Err(invalid_data(message));
+            }
+        }
+
+        let mut hotkey =
+            |name: &str, field: fn(&HotkeyConfig) -> &Option<String>, default: &str| {
+                let value = resolver.get(
+                    &format!("hotkeys.{}", name),
+                    |o| field(&o.hotkeys).clone(),
+                    default.to_string(),
+                );
+
+                let hotkey = Hotkey::parse(&value)?;
+
+                // keys typed without ctrl or alt would never reach the CHIP-8
+                let modifiers = KeyModifiers::CONTROL | KeyModifiers::ALT;
+
+                match keymap.get_key(&hotkey.key) {
+                    Some(key) if !hotkey.modifiers.intersects(modifiers) => {
+                        let message = format!(
+                            "Hotkey {} ({}) is bound to the CHIP-8 key 0x{:X}",
+                            name, value, key
+                        );
+                        Err(invalid_data(message))
+                    }
+                    _ => Ok(hotkey),
+                }
+            };
+
+        let hotkeys = Hotkeys {
+            exit: hotkey("exit", |h| &h.exit, "ctrl+q")?,
+            screenshot: hotkey("screenshot", |h| &h.screenshot, "ctrl+s")?,
+            pause: hotkey("pause", |h| &h.pause, "ctrl+p")?,
+            frame_advance: hotkey("frame_advance", |h| &h.frame_advance, "ctrl+f")?,
+            step: hotkey("step", |h| &h.step, "ctrl+n")?,
+            reset: hotkey("reset", |h| &h.reset, "ctrl+r")?,
+            speed_up: hotkey("speed_up", |h| &h.speed_up, "pageup")?,
+            speed_down: hotkey("speed_down", |h| &h.speed_down, "pagedown")?,
+        };
+
+        Ok(Settings {
+            pixel,
+            theme,
+            fg,
+            bg,
+            clock,
+            steps,
+            persistence: (persistence > 0).then_some(persistence),
+            renderer,
+            scale,
+            address,
+            quirks,
+            keymap,
+            hotkeys,
+            info,
+            entries: resolver.entries,
+        })
+    }
+}
+
+// A ROM file with the options implied by its format
+pub struct RomFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+    pub hash: String,
+    pub options: Options,
+    pub source: Source,
+    // Octo programs are assembled for the default address
+    pub assembled: bool,
+}
+
+impl RomFile {
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        Self::parse(path, fs::read(path)?)
+    }
+
+    // Octo cartridges and sources are assembled, the extension of raw
+    // binaries selects the platform
+    pub fn parse(path: &Path, content: Vec<u8>) -> Result<Self, Error> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let assembled = matches!(extension.as_str(), "gif" | "8o");
+        let (bytes, options) = match extension.as_str() {
+            "gif" => {
+                let cartridge = Cartridge::decode(content.as_slice())?;
+                (
+                    cartridge.assemble()?,
+                    Options::from_octo(&cartridge.options),
+                )
+            }
+            "8o" => {
+                let source = String::from_utf8(content).map_err(|_| {
+                    invalid_data(format!("Invalid Octo source: {}", path.display()))
+                })?;
+                (assembler::assemble(&source)?, Options::default())
+            }
+            _ => {
+                let options = Platform::from_extension(&extension)
+                    .map(Options::from_platform)
+                    .unwrap_or_default();
+                (content, options)
+            }
+        };
+
+        Ok(Self {
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            hash: rom::get_hash(&bytes),
+            bytes,
+            options,
+            source: Source::File,
+            assembled,
+        })
+    }
+
+    // Programs shared by the web frontend, as a URL or its fragment
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        let share = Share::decode(url)?;
+
+        Ok(Self {
+            name: "shared".to_string(),
+            hash: rom::get_hash(&share.program),
+            options: Options::from_share(&share),
+            bytes: share.program,
+            source: Source::Url,
+            assembled: false,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    ConfigFile,
+    File,
+    Database,
+    Url,
+    Rom,
+    CommandLine,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Source::Default => "default",
+            Source::ConfigFile => "config file",
+            Source::File => "rom file",
+            Source::Database => "database",
+            Source::Url => "shared url",
+            Source::Rom => "rom override",
+            Source::CommandLine => "command line",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+pub struct Entry {
+    pub key: String,
+    pub value: String,
+    pub source: Source,
+}
+
+struct Resolver<'a> {
+    layers: Vec<(Source, &'a Options)>,
+    entries: Vec<Entry>,
+    last_source: Source,
+}
+
+impl Resolver<'_> {
+    fn get<T, F>(&mut self, key: &str, field: F, default: T) -> T
+    where
+        T: Serialize,
+        F: Fn(&Options) -> Option<T>,
+    {
+        self.get_or(key, field, (default, Source::Default))
+    }
+
+    fn get_or<T, F>(&mut self, key: &str, field: F, default: (T, Source)) -> T
+    where
+        T: Serialize,
+        F: Fn(&Options) -> Option<T>,
+    {
+        let (value, source) = self
+            .layers
+            .iter()
+            .rev()
+            .find_map(|(source, options)| field(options).map(|value| (value, *source)))
+            .unwrap_or(default);
+
+        self.last_source = source;
+
+        // unset optional values are not listed
+        if let Ok(formatted) = toml::Value::try_from(&value) {
+            self.entries.push(Entry {
+                key: key.to_string(),
+                value: formatted.to_string(),
+                source,
+            });
+        }
+
+        value
+    }
+
+    fn last_source(&self) -> Source {
+        self.last_source
+    }
+}
+
+// The effective configuration
+pub struct Settings {
+    pub pixel: char,
+    pub theme: Option<Theme>,
+    pub fg: PixelColor,
+    pub bg: PixelColor,
+    pub clock: u64,
+    pub steps: u16,
+    pub persistence: Option<u8>,
+    pub renderer: RendererKind,
+    pub scale: usize,
+    pub address: u16,
+    pub quirks: Quirks,
+    pub keymap: Keymap,
+    pub hotkeys: Hotkeys,
+    pub info: Option<RomInfo>,
+    pub entries: Vec<Entry>,
+}
+
+impl Settings {
+    pub fn get_palette(&self) -> Palette {
+        let mut palette = self.theme.map(|theme| theme.palette()).unwrap_or_default();
+
+        palette.set_fg(self.fg.to_rgb());
+        palette.set_bg(self.bg.to_rgb());
+        palette
+    }
+
+    // Prints the settings as TOML, annotated with the origin of each value
+    pub fn print(&self) {
+        let mut section = "";
+
+        for entry in &self.entries {
+            let (current, key) = entry.key.rsplit_once('.').unwrap_or(("", &entry.key));
+
+            if current != section {
+                println!("\n[{}]", current);
+                section = current;
+            }
+
+            println!("{} = {} # {}", key, entry.value, entry.source);
+        }
+    }
+}
+
+pub struct Hotkeys {
+    pub exit: Hotkey,
+    pub screenshot: Hotkey,
+    pub pause: Hotkey,
+    pub frame_advance: Hotkey,
+    pub step: Hotkey,
+    pub reset: Hotkey,
+    pub speed_up: Hotkey,
+    pub speed_down: Hotkey,
+}
+
+pub struct Hotkey {
+    modifiers: KeyModifiers,
+    key: String,
+}
+
+impl Hotkey {
+    // Parses combinations like "ctrl+s" or "ctrl+alt++"
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let (prefix, key) = match value.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None => value.rsplit_once('+').unwrap_or(("", value)),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+
+        for modifier in prefix.split('+').filter(|part| !part.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid_data(format!("Invalid hotkey: {}", value))),
+            };
+        }
+
+        if key.is_empty() {
+            return Err(invalid_data(format!("Invalid hotkey: {}", value)));
+        }
+
+        Ok(Self {
+            modifiers,
+            key: keymap::normalize(key),
+        })
+    }
+
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        event.modifiers == self.modifiers
+            && get_key_name(event.code).as_deref() == Some(self.key.as_str())
+    }
+}
+
+pub fn get_default_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("yace").join("config.toml"))
+}
+
+pub fn get_key_name(code: KeyCode) -> Option<String> {
+    let name = match code {
+        KeyCode::Char(char) => char.to_string(),
+        KeyCode::F(number) => format!("f{}", number),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Home => "home".to_string(),
+        KeyCode::End => "end".to_string(),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        KeyCode::Insert => "insert".to_string(),
+        KeyCode::Delete => "delete".to_string(),
+        _ => return None,
+    };
+
+    Some(keymap::normalize(&name))
+}
+
+fn parse_key(digit: &str) -> Result<u8, Error> {
+    let value = digit.trim_start_matches("0x");
+
+    u8::from_str_radix(value, 16)
+        .ok()
+        .filter(|&key| key < 16)
+        .ok_or_else(|| invalid_data(format!("Invalid CHIP-8 key: {}", digit)))
+}
+
+fn parse_address(value: &str) -> Result<u16, String> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+
+    parsed.map_err(|_| format!("Invalid address: {}", value))
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,304 @@
+mod app;
+mod browser;
+mod color;
+mod config;
+mod renderer;
+mod watcher;
+
+use app::{App, Exit};
+use browser::Browser;
+use clap::{arg, command, Parser, Subcommand};
+use config::{Config, Options, RomFile, Settings};
+use crossterm::{
+    cursor,
+    event::{
+        self, KeyboardEnhancementFlags as KeyFlag, PushKeyboardEnhancementFlags as PushKeyFlag,
+    },
+    terminal, QueueableCommand,
+};
+use std::{
+    fs::File,
+    io::{stdout, BufWriter, Error, ErrorKind},
+    panic::{self, PanicInfo},
+    path::{Path, PathBuf},
+};
+use watcher::Watcher;
+use yace::{
+    recorder::GifRecorder,
+    screenshot::{Format, Screenshot},
+};
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    options: Options,
+
+    /// Save a screenshot when exiting (png, pbm, pgm or txt)
+    #[arg(long, value_name = "PATH")]
+    screenshot_on_exit: Option<PathBuf>,
+
+    /// Record the gameplay into an animated GIF, each emulated frame lasting a 60 Hz timer tick
+    #[arg(long, value_name = "PATH")]
+    record: Option<PathBuf>,
+
+    /// Run a program shared by the web frontend, as a URL or its fragment
+    #[arg(long, value_name = "URL", conflicts_with = "path")]
+    from_url: Option<String>,
+
+    /// Reload the ROM when the file changes
+    #[arg(long, conflicts_with = "from_url")]
+    watch: bool,
+
+    /// Keep the current keymap and speed when the ROM is reloaded
+    #[arg(long, requires = "watch")]
+    keep_settings: bool,
+
+    /// Config file path, defaults to ~/.config/yace/config.toml
+    #[arg(long, value_name = "PATH", global = true)]
+    config: Option<PathBuf>,
+
+    /// ROM file path: a binary, an Octo source (.8o) or cartridge (.gif), a directory or a zip archive
+    #[arg(required_unless_present = "from_url")]
+    path: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the effective configuration and where each value comes from
+    Config {
+        /// ROM file used to apply the per-ROM overrides
+        path: Option<String>,
+    },
+}
+
+impl Cli {
+    fn run(&self) -> Result<(), Error> {
+        let config = Config::load(self.config.as_deref())?;
+
+        match &self.command {
+            Some(Command::Config { path }) => self.print_config(&config, path.as_deref()),
+            None => self.run_rom(&config),
+        }
+    }
+
+    fn run_rom(&self, config: &Config) -> Result<(), Error> {
+        if let Some(url) = &self.from_url {
+            return self.play(config, &RomFile::from_url(url)?, None);
+        }
+
+        let path = self
+            .path
+            .as_deref()
+            .map(Path::new)
+            .ok_or_else(|| Error::other("Missing ROM path"))?;
+
+        if browser::is_collection(path) {
+            // each game would overwrite the recording of the previous one
+            if self.record.is_some() {
+                return Err(Error::other("Cannot record while browsing a collection"));
+            }
+
+            if self.watch {
+                return Err(Error::other("Cannot watch a collection, only a ROM file"));
+            }
+
+            return self.browse(config, path);
+        }
+
+        let watcher = self.watch.then(|| Watcher::new(path));
+
+        self.play(config, &RomFile::read(path)?, watcher)
+    }
+
+    // Goes back to the menu when a game is exited
+    fn browse(&self, config: &Config, path: &Path) -> Result<(), Error> {
+        let entries = browser::read_collection(path)?;
+        let mut browser = Browser::new(&entries);
+
+        loop {
+            init_screen(browser::MIN_SIZE)?;
+
+            let selected = browser.select();
+
+            cleanup()?;
+
+            let Some(entry) = selected? else {
+                return Ok(());
+            };
+
+            let rom = RomFile::parse(&entry.path, entry.content.clone())?;
+
+            self.play(config, &rom, None)?;
+        }
+    }
+
+    fn play(&self, config: &Config, rom: &RomFile, watcher: Option<Watcher>) -> Result<(), Error> {
+        let settings = config.resolve(&self.options, Some(rom))?;
+        let recorder = self.create_recorder(&settings)?;
+        let name = get_name(&settings, rom);
+        let mut app = App::new(settings, name, &rom.bytes)?;
+
+        if let Some(recorder) = recorder {
+            app.set_recorder(recorder);
+        }
+
+        init_screen(app.get_required_size())?;
+
+        let result = self.run_app(config, &mut app, watcher);
+
+        cleanup()?;
+
+        // the last frame is most useful when the emulation failed, whose
+        // error is still the one reported
+        let screenshot = self
+            .screenshot_on_exit
+            .as_ref()
+            .map(|path| save_screenshot(app.get_settings(), app.get_display_buffer(), path));
+
+        result?;
+        screenshot.transpose()?;
+        app.finish()
+    }
+
+    fn run_app(
+        &self,
+        config: &Config,
+        app: &mut App,
+        mut watcher: Option<Watcher>,
+    ) -> Result<(), Error> {
+        loop {
+            match app.run(watcher.as_mut())? {
+                Exit::Quit => return Ok(()),
+                Exit::Reload => {
+                    let Some(watcher) = &watcher else {
+                        continue;
+                    };
+
+                    // a broken build is reported without leaving the emulator
+                    if let Err(error) = self.reload(config, app, watcher.get_path()) {
+                        app.set_notice(format!("RELOAD FAILED: {}", error));
+                    }
+                }
+            }
+        }
+    }
+
+    fn reload(&self, config: &Config, app: &mut App, path: &Path) -> Result<(), Error> {
+        let rom = RomFile::read(path)?;
+        let settings = config.resolve(&self.options, Some(&rom))?;
+        let name = get_name(&settings, &rom);
+
+        app.reload(settings, name, &rom.bytes, self.keep_settings)
+    }
+
+    fn print_config(&self, config: &Config, path: Option<&str>) -> Result<(), Error> {
+        let rom = path
+            .map(|path| RomFile::read(Path::new(path)))
+            .transpose()?;
+        let settings = config.resolve(&self.options, rom.as_ref())?;
+
+        match &config.path {
+            Some(path) => println!("# config file: {}", path.display()),
+            None => println!("# config file: none"),
+        }
+
+        if let (Some(path), Some(rom)) = (path, &rom) {
+            println!("# rom: {} (sha1: {})", path, rom.hash);
+        }
+
+        if let Some(info) = &settings.info {
+            println!("# title: {} ({})", info.title, info.platform.name());
+        }
+
+        println!();
+        settings.print();
+
+        Ok(())
+    }
+
+    fn create_recorder(
+        &self,
+        settings: &Settings,
+    ) -> Result<Option<GifRecorder<BufWriter<File>>>, Error> {
+        let Some(path) = &self.record else {
+            return Ok(None);
+        };
+
+        let writer = BufWriter::new(File::create(path)?);
+        let recorder = GifRecorder::new(writer, settings.get_palette(), settings.scale)?;
+
+        Ok(Some(recorder))
+    }
+}
+
+fn get_name(settings: &Settings, rom: &RomFile) -> String {
+    match &settings.info {
+        Some(info) => info.title.clone(),
+        None => rom.name.clone(),
+    }
+}
+
+fn init_screen(required_size: (usize, usize)) -> Result<(), Error> {
+    let (width, height) = terminal::size()?;
+    let (required_width, required_height) = required_size;
+
+    if (width as usize) < required_width || (height as usize) < required_height {
+        let message = format!(
+            "The required terminal size is {}x{}",
+            required_width, required_height
+        );
+
+        return Err(Error::new(ErrorKind::Other, message));
+    }
+
+    panic::set_hook(Box::new(|info: &PanicInfo| {
+        println!("{:?}", info);
+        cleanup().unwrap();
+    }));
+
+    stdout()
+        .queue(PushKeyFlag(KeyFlag::REPORT_EVENT_TYPES))?
+        .queue(PushKeyFlag(KeyFlag::REPORT_ALL_KEYS_AS_ESCAPE_CODES))?
+        .queue(PushKeyFlag(KeyFlag::DISAMBIGUATE_ESCAPE_CODES))?
+        .queue(terminal::EnterAlternateScreen)?
+        .queue(cursor::Hide)?;
+
+    terminal::enable_raw_mode()
+}
+
+pub(crate) fn save_screenshot(
+    settings: &Settings,
+    buffer: &[u8],
+    path: &Path,
+) -> Result<(), Error> {
+    let format = Format::from_path(path).unwrap_or(Format::Png);
+    let writer = BufWriter::new(File::create(path)?);
+
+    Screenshot::new(buffer)
+        .with_scale(settings.scale)
+        .with_palette(settings.get_palette())
+        .write(format, writer)
+}
+
+fn cleanup() -> Result<(), Error> {
+    stdout()
+        .queue(event::PopKeyboardEnhancementFlags)?
+        .queue(event::PopKeyboardEnhancementFlags)?
+        .queue(event::PopKeyboardEnhancementFlags)?
+        .queue(terminal::LeaveAlternateScreen)?
+        .queue(cursor::Show)?;
+
+    terminal::disable_raw_mode()
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    if let Err(error) = cli.run() {
+        println!("error: {}", error);
+    }
+}
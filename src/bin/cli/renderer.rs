@@ -0,0 +1,155 @@
+use clap::ValueEnum;
+use crossterm::style::{Color, SetBackgroundColor, SetForegroundColor};
+use serde::{Deserialize, Serialize};
+use yace::display::{HEIGHT, WIDTH};
+
+const SHADES: [char; 3] = ['░', '▒', '▓'];
+// Packed glyphs cannot be shaded, fading pixels are turned off halfway
+const LIT_LEVEL: u8 = 128;
+
+const HALF_BLOCKS: [char; 4] = [' ', '▀', '▄', '█'];
+
+#[rustfmt::skip]
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛',
+    '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+const BRAILLE_BASE: u32 = 0x2800;
+
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum RendererKind {
+    /// One character per pixel
+    Full,
+    /// 1x2 pixels per character using half blocks
+    Half,
+    /// 2x2 pixels per character using quadrant blocks
+    Quadrant,
+    /// 2x4 pixels per character using braille patterns
+    Braille,
+}
+
+impl RendererKind {
+    fn cell_size(&self) -> (usize, usize) {
+        match self {
+            RendererKind::Full => (1, 1),
+            RendererKind::Half => (1, 2),
+            RendererKind::Quadrant => (2, 2),
+            RendererKind::Braille => (2, 4),
+        }
+    }
+
+    pub fn required_size(&self) -> (usize, usize) {
+        let (width, height) = self.cell_size();
+
+        (WIDTH.div_ceil(width), HEIGHT.div_ceil(height))
+    }
+
+    fn dot(&self, dx: usize, dy: usize) -> u8 {
+        match self {
+            RendererKind::Braille => BRAILLE_DOTS[dy][dx],
+            _ => 1 << (dy * self.cell_size().0 + dx),
+        }
+    }
+
+    fn glyph(&self, mask: u8) -> char {
+        match self {
+            RendererKind::Full => unreachable!(),
+            RendererKind::Half => HALF_BLOCKS[mask as usize],
+            RendererKind::Quadrant => QUADRANTS[mask as usize],
+            RendererKind::Braille => char::from_u32(BRAILLE_BASE + mask as u32).unwrap_or(' '),
+        }
+    }
+}
+
+pub struct Renderer {
+    kind: RendererKind,
+    pixel: char,
+    fg: Color,
+    bg: Color,
+}
+
+impl Renderer {
+    pub fn new(kind: RendererKind, pixel: char, fg: Color, bg: Color) -> Self {
+        Self {
+            kind,
+            pixel,
+            fg,
+            bg,
+        }
+    }
+
+    pub fn required_size(&self) -> (usize, usize) {
+        self.kind.required_size()
+    }
+
+    // The buffer holds either plain pixels or shaded intensity levels
+    pub fn render(&self, buffer: &[u8], shaded: bool) -> String {
+        match self.kind {
+            RendererKind::Full => self.render_full(buffer, shaded),
+            _ => self.render_packed(buffer, shaded),
+        }
+    }
+
+    fn render_full(&self, buffer: &[u8], shaded: bool) -> String {
+        buffer
+            .iter()
+            .enumerate()
+            .map(|(i, &level)| {
+                let (color, pixel) = self.get_cell(level, shaded);
+                let fg = SetForegroundColor(color);
+                let new_line = (i + 1) % WIDTH == 0 && (i + 1) != WIDTH * HEIGHT;
+                let end = if new_line { "\r\n" } else { "" };
+
+                format!("{}{}{}", fg, pixel, end)
+            })
+            .collect()
+    }
+
+    fn render_packed(&self, buffer: &[u8], shaded: bool) -> String {
+        let threshold = if shaded { LIT_LEVEL } else { 1 };
+        let (cell_width, cell_height) = self.kind.cell_size();
+        let (columns, rows) = self.kind.required_size();
+        let mut output = format!(
+            "{}{}",
+            SetForegroundColor(self.fg),
+            SetBackgroundColor(self.bg)
+        );
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let mut mask = 0;
+
+                for dy in 0..cell_height {
+                    for dx in 0..cell_width {
+                        let x = column * cell_width + dx;
+                        let y = row * cell_height + dy;
+
+                        if x < WIDTH && y < HEIGHT && buffer[y * WIDTH + x] >= threshold {
+                            mask |= self.kind.dot(dx, dy);
+                        }
+                    }
+                }
+
+                output.push(self.kind.glyph(mask));
+            }
+
+            if row + 1 != rows {
+                output.push_str("\r\n");
+            }
+        }
+
+        output
+    }
+
+    fn get_cell(&self, level: u8, shaded: bool) -> (Color, char) {
+        match (level, shaded) {
+            (0, _) => (self.bg, self.pixel),
+            (u8::MAX, true) | (_, false) => (self.fg, self.pixel),
+            (level, true) => (self.fg, SHADES[level as usize * SHADES.len() / 255]),
+        }
+    }
+}
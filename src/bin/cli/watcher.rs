@@ -0,0 +1,54 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Polls the modification time of a ROM file
+pub struct Watcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    checked: Instant,
+}
+
+impl Watcher {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            modified: get_modified(path),
+            checked: Instant::now(),
+        }
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    // Returns true once for each change of the file
+    pub fn poll(&mut self) -> bool {
+        if self.checked.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+
+        self.checked = Instant::now();
+
+        // the file can be missing for a moment while it is rebuilt
+        let modified = get_modified(&self.path);
+
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+
+        self.modified = modified;
+
+        true
+    }
+}
+
+fn get_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
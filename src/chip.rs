@@ -1,7 +1,14 @@
-use crate::{display::Display, keyboard::Keyboard, memory::Memory};
+use crate::{
+    database::{self, RomInfo},
+    display::{Display, HEIGHT},
+    keyboard::Keyboard,
+    memory::{Memory, MEM_SIZE},
+    quirks::Quirks,
+    rom::{self, DEFAULT_ADDRESS},
+    state::State,
+};
 use rand::{rngs::ThreadRng, thread_rng, Rng};
-
-const PRG_START: u16 = 0x200;
+use std::io::{Error, ErrorKind};
 
 #[derive(Debug)]
 pub struct Chip {
@@ -13,10 +20,14 @@ pub struct Chip {
     sp: u8,
     stack: [u16; 16],
     memory: Memory,
+    program: Vec<u8>,
+    address: u16,
+    info: Option<RomInfo>,
     rng: ThreadRng,
 
     pub display: Display,
     pub keyboard: Keyboard,
+    pub quirks: Quirks,
 }
 
 impl Default for Chip {
@@ -26,50 +37,195 @@ impl Default for Chip {
             i: 0,
             dt: 0,
             st: 0,
-            pc: PRG_START,
+            pc: DEFAULT_ADDRESS,
             sp: 0,
             stack: [0; 16],
             memory: Memory::default(),
+            program: Vec::new(),
+            address: DEFAULT_ADDRESS,
+            info: None,
             keyboard: Keyboard::default(),
             display: Display::default(),
+            quirks: Quirks::default(),
             rng: thread_rng(),
         }
     }
 }
 
 impl Chip {
-    pub fn new(program: &[u8]) -> Self {
+    pub fn new(program: &[u8]) -> Result<Self, Error> {
         let mut chip = Chip::default();
 
-        chip.load(program);
-        chip
+        chip.load(program)?;
+        Ok(chip)
     }
 
+    // Restarts the loaded program, the rest of the memory is kept as is
     pub fn reset(&mut self) {
         self.v = [0; 16];
         self.i = 0;
         self.dt = 0;
         self.st = 0;
-        self.pc = PRG_START;
+        self.pc = self.address;
         self.sp = 0;
         self.stack = [0; 16];
-        self.memory.clear();
         self.keyboard.reset();
         self.display.clear();
+        self.write_program();
+    }
+
+    // Restarts the loaded program with a cleared memory
+    pub fn power_cycle(&mut self) {
+        self.memory.clear();
+        self.reset();
+    }
+
+    pub fn unload(&mut self) {
+        self.program.clear();
+        self.info = None;
+        self.power_cycle();
+    }
+
+    // Known ROMs are loaded with the address and quirks from the database,
+    // the others with the defaults
+    pub fn load(&mut self, program: &[u8]) -> Result<(), Error> {
+        let info = database::lookup_program(program);
+        let address = info.as_ref().map_or(DEFAULT_ADDRESS, |info| info.address);
+        let quirks = info.as_ref().map_or(Quirks::default(), |info| info.quirks);
+
+        self.load_with_info(program, address, info)?;
+        self.quirks = quirks;
+
+        Ok(())
+    }
+
+    // The program counter starts at the load address after a reset
+    pub fn load_at(&mut self, program: &[u8], address: u16) -> Result<(), Error> {
+        self.load_with_info(program, address, database::lookup_program(program))
+    }
+
+    fn load_with_info(
+        &mut self,
+        program: &[u8],
+        address: u16,
+        info: Option<RomInfo>,
+    ) -> Result<(), Error> {
+        rom::validate(program, address)?;
+
+        self.program = program.to_vec();
+        self.address = address;
+        self.pc = address;
+        self.info = info;
+        self.write_program();
+
+        Ok(())
+    }
+
+    pub fn get_info(&self) -> Option<&RomInfo> {
+        self.info.as_ref()
+    }
+
+    pub fn get_program(&self) -> &[u8] {
+        &self.program
+    }
+
+    pub fn get_address(&self) -> u16 {
+        self.address
+    }
+
+    pub fn get_hash(&self) -> String {
+        rom::get_hash(&self.program)
+    }
+
+    pub fn get_registers(&self) -> &[u8] {
+        &self.v
+    }
+
+    pub fn get_i(&self) -> u16 {
+        self.i
+    }
+
+    pub fn get_pc(&self) -> u16 {
+        self.pc
+    }
+
+    // Only the return addresses currently on the stack
+    pub fn get_stack(&self) -> &[u16] {
+        &self.stack[..self.sp as usize]
+    }
+
+    pub fn get_delay_timer(&self) -> u8 {
+        self.dt as u8
+    }
+
+    pub fn get_sound_timer(&self) -> u8 {
+        self.st as u8
+    }
+
+    pub fn get_memory(&self) -> &[u8] {
+        self.memory.get_buffer()
+    }
+
+    pub fn save_state(&self) -> State {
+        State {
+            v: self.v,
+            i: self.i,
+            dt: self.dt as u8,
+            st: self.st as u8,
+            pc: self.pc,
+            stack: self.get_stack().to_vec(),
+            memory: self.memory.get_buffer().to_vec(),
+            rows: self.display.get_rows().to_vec(),
+            quirks: self.quirks,
+            address: self.address,
+            program: self.program.clone(),
+        }
     }
 
-    pub fn load(&mut self, program: &[u8]) {
-        let start = PRG_START as usize;
-        let end = start + program.len();
+    // The pressed keys are released since they are not part of the state
+    pub fn load_state(&mut self, state: &State) -> Result<(), Error> {
+        let is_consistent = state.memory.len() == MEM_SIZE
+            && state.rows.len() == HEIGHT
+            && state.stack.len() <= self.stack.len();
+
+        if !is_consistent {
+            let message = "Invalid save state: inconsistent sizes";
+            return Err(Error::new(ErrorKind::InvalidData, message));
+        }
+
+        rom::validate(&state.program, state.address)?;
 
-        self.memory.write_slice(start, end, program);
+        self.v = state.v;
+        self.i = state.i;
+        self.dt = state.dt as u16;
+        self.st = state.st as u16;
+        self.pc = state.pc;
+        self.sp = state.stack.len() as u8;
+        self.stack = [0; 16];
+        self.stack[..state.stack.len()].copy_from_slice(&state.stack);
+        self.memory.write_slice(0, MEM_SIZE, &state.memory);
+        self.display.set_rows(&state.rows);
+        self.keyboard.reset();
+        self.quirks = state.quirks;
+        self.address = state.address;
+        self.program = state.program.clone();
+        self.info = database::lookup_program(&self.program);
+
+        Ok(())
     }
 
-    pub fn tick(&mut self) {
-        let instruction = self.fetch();
+    fn write_program(&mut self) {
+        let start = self.address as usize;
+        let end = start + self.program.len();
+
+        self.memory.write_slice(start, end, &self.program);
+    }
+
+    pub fn tick(&mut self) -> Result<(), Error> {
+        let instruction = self.fetch()?;
 
         self.display.clear_status();
-        self.execute(instruction);
+        self.execute(instruction)
     }
 
     pub fn update_timers(&mut self) {
@@ -77,14 +233,16 @@ impl Chip {
         self.st = self.st.saturating_sub(1);
     }
 
-    pub fn fetch(&self) -> u16 {
+    pub fn fetch(&self) -> Result<u16, Error> {
+        self.check_memory(self.pc, 2)?;
+
         let msb = self.memory.read(self.pc) as u16;
         let lsb = self.memory.read(self.pc + 1) as u16;
 
-        (msb << 8) | lsb
+        Ok((msb << 8) | lsb)
     }
 
-    pub fn execute(&mut self, opcode: u16) {
+    pub fn execute(&mut self, opcode: u16) -> Result<(), Error> {
         let nibbles = (
             ((opcode & 0xF000) >> 12) as u8,
             ((opcode & 0x0F00) >> 8) as u8,
@@ -97,9 +255,9 @@ impl Chip {
 
         match nibbles {
             (0x0, 0x0, 0xE, 0x0) => self.cls(),
-            (0x0, 0x0, 0xE, 0xE) => self.ret(),
+            (0x0, 0x0, 0xE, 0xE) => self.ret()?,
             (0x1, _, _, _) => self.jp_addr(nnn),
-            (0x2, _, _, _) => self.call_addr(nnn),
+            (0x2, _, _, _) => self.call_addr(nnn)?,
             (0x3, x, _, _) => self.se_vx_b(x, kk),
             (0x4, x, _, _) => self.sne_vx_b(x, kk),
             (0x5, x, y, 0x0) => self.se_vx_vy(x, y),
@@ -111,14 +269,14 @@ impl Chip {
             (0x8, x, y, 0x3) => self.xor_vx_vy(x, y),
             (0x8, x, y, 0x4) => self.add_vx_vy(x, y),
             (0x8, x, y, 0x5) => self.sub_vx_vy(x, y),
-            (0x8, x, _, 0x6) => self.shr_vx(x),
+            (0x8, x, y, 0x6) => self.shr_vx_vy(x, y),
             (0x8, x, y, 0x7) => self.sub_vy_vx(y, x),
-            (0x8, x, _, 0xE) => self.shl_vx(x),
+            (0x8, x, y, 0xE) => self.shl_vx_vy(x, y),
             (0x9, x, y, 0x0) => self.sne_vx_vy(x, y),
             (0xA, _, _, _) => self.ld_i_addr(nnn),
             (0xB, _, _, _) => self.jp_v_addr(nnn),
             (0xC, x, _, _) => self.rnd_vx_b(x, kk),
-            (0xD, x, y, n) => self.drw_x_y_n(x, y, n),
+            (0xD, x, y, n) => self.drw_x_y_n(x, y, n)?,
             (0xE, x, 0x9, 0xE) => self.skp_vx(x),
             (0xE, x, 0xA, 0x1) => self.sknp_vx(x),
             (0xF, x, 0x0, 0x7) => self.ld_vx_dt(x),
@@ -127,13 +285,16 @@ impl Chip {
             (0xF, x, 0x1, 0x8) => self.ld_st_vx(x),
             (0xF, x, 0x1, 0xE) => self.add_i_vx(x),
             (0xF, x, 0x2, 0x9) => self.ld_f_vx(x),
-            (0xF, x, 0x3, 0x3) => self.ld_b_vx(x),
-            (0xF, x, 0x5, 0x5) => self.ld_i_vx(x),
-            (0xF, x, 0x6, 0x5) => self.ld_vx_i(x),
+            (0xF, x, 0x3, 0x3) => self.ld_b_vx(x)?,
+            (0xF, x, 0x5, 0x5) => self.ld_i_vx(x)?,
+            (0xF, x, 0x6, 0x5) => self.ld_vx_i(x)?,
             _ => {
-                panic!("Unsupported instruction: {:x}", opcode);
+                let message = format!("Unsupported instruction: {:04X}", opcode);
+                return Err(self.runtime_error(&message));
             }
         }
+
+        Ok(())
     }
 
     fn cls(&mut self) {
@@ -141,9 +302,15 @@ impl Chip {
         self.increment();
     }
 
-    fn ret(&mut self) {
+    fn ret(&mut self) -> Result<(), Error> {
+        if self.sp == 0 {
+            return Err(self.runtime_error("Stack underflow"));
+        }
+
         self.sp -= 1;
         self.jump(self.stack[self.sp as usize]);
+
+        Ok(())
     }
 
     fn jp_addr(&mut self, addr: u16) {
@@ -151,13 +318,24 @@ impl Chip {
     }
 
     fn jp_v_addr(&mut self, addr: u16) {
-        self.jump(addr + self.v(0) as u16);
+        let x = match self.quirks.jumping {
+            true => (addr >> 8) as u8,
+            false => 0,
+        };
+
+        self.jump(addr + self.v(x) as u16);
     }
 
-    fn call_addr(&mut self, addr: u16) {
+    fn call_addr(&mut self, addr: u16) -> Result<(), Error> {
+        if self.sp as usize == self.stack.len() {
+            return Err(self.runtime_error("Stack overflow"));
+        }
+
         self.stack[self.sp as usize] = self.pc + 2;
         self.sp += 1;
         self.jump(addr);
+
+        Ok(())
     }
 
     fn se_vx_b(&mut self, x: u8, byte: u8) {
@@ -213,29 +391,42 @@ impl Chip {
         self.increment();
     }
 
-    fn ld_b_vx(&mut self, x: u8) {
+    fn ld_b_vx(&mut self, x: u8) -> Result<(), Error> {
+        self.check_memory(self.i, 3)?;
         self.memory.write(self.i, self.v(x) / 100);
         self.memory.write(self.i + 1, (self.v(x) / 10) % 10);
         self.memory.write(self.i + 2, (self.v(x) % 100) % 10);
         self.increment();
+
+        Ok(())
     }
 
-    fn ld_i_vx(&mut self, x: u8) {
+    fn ld_i_vx(&mut self, x: u8) -> Result<(), Error> {
+        self.check_memory(self.i, x as usize + 1)?;
+
         let x = x as usize;
         let i = self.i as usize;
         let slice = &self.v[0..x + 1];
 
         self.memory.write_slice(i, i + x + 1, slice);
+        self.increment_i(x + 1);
         self.increment();
+
+        Ok(())
     }
 
-    fn ld_vx_i(&mut self, x: u8) {
+    fn ld_vx_i(&mut self, x: u8) -> Result<(), Error> {
+        self.check_memory(self.i, x as usize + 1)?;
+
         let x = x as usize;
         let i = self.i as usize;
         let slice = self.memory.read_slice(i, i + x + 1);
 
         self.v[0..x + 1].copy_from_slice(slice);
+        self.increment_i(x + 1);
         self.increment();
+
+        Ok(())
     }
 
     fn add_vx_b(&mut self, x: u8, rhs: u8) {
@@ -279,31 +470,36 @@ impl Chip {
 
     fn or_vx_vy(&mut self, x: u8, y: u8) {
         self.write_register(x, self.v(x) | self.v(y));
+        self.reset_flag();
         self.increment();
     }
 
     fn and_vx_vy(&mut self, x: u8, y: u8) {
         self.write_register(x, self.v(x) & self.v(y));
+        self.reset_flag();
         self.increment();
     }
 
     fn xor_vx_vy(&mut self, x: u8, y: u8) {
         self.write_register(x, self.v(x) ^ self.v(y));
+        self.reset_flag();
         self.increment();
     }
 
-    fn shr_vx(&mut self, x: u8) {
-        let shift_flag = self.v(x) & 1 == 1;
+    fn shr_vx_vy(&mut self, x: u8, y: u8) {
+        let value = self.shift_source(x, y);
+        let shift_flag = value & 1 == 1;
 
-        self.write_register(x, self.v(x).wrapping_shr(1));
+        self.write_register(x, value.wrapping_shr(1));
         self.set_flag(shift_flag);
         self.increment();
     }
 
-    fn shl_vx(&mut self, x: u8) {
-        let shift_flag = self.v(x) >> 7 == 1;
+    fn shl_vx_vy(&mut self, x: u8, y: u8) {
+        let value = self.shift_source(x, y);
+        let shift_flag = value >> 7 == 1;
 
-        self.write_register(x, self.v(x).wrapping_shl(1));
+        self.write_register(x, value.wrapping_shl(1));
         self.set_flag(shift_flag);
         self.increment();
     }
@@ -315,23 +511,29 @@ impl Chip {
         self.increment();
     }
 
-    fn drw_x_y_n(&mut self, x: u8, y: u8, n: u8) {
+    fn drw_x_y_n(&mut self, x: u8, y: u8, n: u8) -> Result<(), Error> {
+        self.check_memory(self.i, n as usize)?;
+
         let i = self.i as usize;
         let x = self.v(x) as usize;
         let y = self.v(y) as usize;
         let sprite = self.memory.read_slice(i, i + n as usize);
-        let collision = self.display.load_sprite(x, y, sprite);
+        let clip = self.quirks.clipping;
+        let collision = self.display.load_sprite(x, y, sprite, clip);
 
         self.set_flag(collision);
         self.increment();
+
+        Ok(())
     }
 
+    // Only the low nibble of VX selects the key, like on the COSMAC VIP
     fn skp_vx(&mut self, x: u8) {
-        self.skip_if(self.keyboard.is_pressed(self.v(x)));
+        self.skip_if(self.keyboard.is_pressed(self.v(x) & 0xF));
     }
 
     fn sknp_vx(&mut self, x: u8) {
-        self.skip_if(!self.keyboard.is_pressed(self.v(x)));
+        self.skip_if(!self.keyboard.is_pressed(self.v(x) & 0xF));
     }
 
     fn write_register(&mut self, x: u8, value: u8) {
@@ -354,6 +556,39 @@ impl Chip {
         self.v[0xF] = if cond { 1 } else { 0 };
     }
 
+    fn reset_flag(&mut self) {
+        if self.quirks.vf_reset {
+            self.set_flag(false);
+        }
+    }
+
+    fn increment_i(&mut self, offset: usize) {
+        if self.quirks.memory {
+            self.i = self.i.wrapping_add(offset as u16);
+        }
+    }
+
+    fn shift_source(&self, x: u8, y: u8) -> u8 {
+        match self.quirks.shifting {
+            true => self.v(x),
+            false => self.v(y),
+        }
+    }
+
+    fn check_memory(&self, addr: u16, len: usize) -> Result<(), Error> {
+        if addr as usize + len > MEM_SIZE {
+            let message = format!("Memory access out of range: 0x{:X}", addr);
+            return Err(self.runtime_error(&message));
+        }
+
+        Ok(())
+    }
+
+    fn runtime_error(&self, message: &str) -> Error {
+        let message = format!("{} at 0x{:03X}", message, self.pc);
+        Error::new(ErrorKind::InvalidData, message)
+    }
+
     #[inline]
     fn v(&self, x: u8) -> u8 {
         self.v[x as usize]
@@ -0,0 +1,148 @@
+use crate::{
+    palette::Rgb,
+    platform::Platform,
+    quirks::Quirks,
+    rom::{self, DEFAULT_ADDRESS},
+};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::OnceLock,
+};
+
+// Subset of the CHIP-8 database (https://github.com/chip-8/chip-8-database),
+// the files can be replaced by the upstream ones as they use the same format
+const HASHES: &str = include_str!("../database/sha1-hashes.json");
+const PROGRAMS: &str = include_str!("../database/programs.json");
+
+#[derive(Deserialize)]
+struct Program {
+    title: String,
+    #[serde(default)]
+    authors: Vec<String>,
+    #[serde(default)]
+    roms: HashMap<String, RomEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RomEntry {
+    #[serde(default)]
+    platforms: Vec<String>,
+    #[serde(default)]
+    quirky_platforms: HashMap<String, QuirkFlags>,
+    tickrate: Option<u32>,
+    start_address: Option<u16>,
+    colors: Option<Colors>,
+    #[serde(default)]
+    keys: BTreeMap<String, u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuirkFlags {
+    shift: Option<bool>,
+    memory_increment_by_x: Option<bool>,
+    memory_leave_i_unchanged: Option<bool>,
+    wrap: Option<bool>,
+    jump: Option<bool>,
+    logic: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct Colors {
+    #[serde(default)]
+    pixels: Vec<String>,
+}
+
+struct Database {
+    hashes: HashMap<String, usize>,
+    programs: Vec<Program>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RomInfo {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub platform: Platform,
+    pub quirks: Quirks,
+    pub tickrate: u32,
+    pub address: u16,
+    // Indexed like the palette colors, starting with the background
+    pub colors: Vec<Rgb>,
+    // Hints like "up" or "a" with the matching CHIP-8 key
+    pub keys: Vec<(String, u8)>,
+}
+
+impl QuirkFlags {
+    fn apply(&self, quirks: &mut Quirks) {
+        let set = |quirk: &mut bool, value: Option<bool>| {
+            if let Some(value) = value {
+                *quirk = value;
+            }
+        };
+
+        set(&mut quirks.vf_reset, self.logic);
+        set(&mut quirks.shifting, self.shift);
+        set(&mut quirks.jumping, self.jump);
+        set(&mut quirks.clipping, self.wrap.map(|wrap| !wrap));
+
+        if self.memory_increment_by_x == Some(true) || self.memory_leave_i_unchanged == Some(true) {
+            quirks.memory = false;
+        }
+    }
+}
+
+impl RomEntry {
+    fn to_info(&self, program: &Program) -> RomInfo {
+        let (platform, id) = self
+            .platforms
+            .iter()
+            .find_map(|id| Platform::from_id(id).map(|platform| (platform, id.as_str())))
+            .unwrap_or((Platform::default(), Platform::default().id()));
+        let mut quirks = platform.quirks();
+
+        if let Some(flags) = self.quirky_platforms.get(id) {
+            flags.apply(&mut quirks);
+        }
+
+        let colors = self
+            .colors
+            .iter()
+            .flat_map(|colors| &colors.pixels)
+            .filter_map(|color| Rgb::from_hex(color))
+            .collect();
+
+        RomInfo {
+            title: program.title.clone(),
+            authors: program.authors.clone(),
+            platform,
+            quirks,
+            tickrate: self.tickrate.unwrap_or(platform.default_tickrate()),
+            address: self.start_address.unwrap_or(DEFAULT_ADDRESS),
+            colors,
+            keys: self.keys.clone().into_iter().collect(),
+        }
+    }
+}
+
+fn get_database() -> &'static Database {
+    static DATABASE: OnceLock<Database> = OnceLock::new();
+
+    DATABASE.get_or_init(|| Database {
+        hashes: serde_json::from_str(HASHES).expect("invalid embedded ROM hashes"),
+        programs: serde_json::from_str(PROGRAMS).expect("invalid embedded ROM database"),
+    })
+}
+
+pub fn lookup(hash: &str) -> Option<RomInfo> {
+    let database = get_database();
+    let hash = hash.to_lowercase();
+    let program = database.programs.get(*database.hashes.get(&hash)?)?;
+
+    program.roms.get(&hash).map(|rom| rom.to_info(program))
+}
+
+pub fn lookup_program(program: &[u8]) -> Option<RomInfo> {
+    lookup(&rom::get_hash(program))
+}
@@ -0,0 +1,75 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub address: u16,
+    pub opcode: u16,
+    pub text: String,
+}
+
+// Decodes the instructions starting at an address, data in between the code
+// is decoded as well since both cannot be told apart
+pub fn disassemble_range(memory: &[u8], start: u16, count: usize) -> Vec<Line> {
+    (0..count)
+        .map(|index| start as usize + index * 2)
+        .take_while(|&address| address + 1 < memory.len())
+        .map(|address| {
+            let opcode = u16::from_be_bytes([memory[address], memory[address + 1]]);
+
+            Line {
+                address: address as u16,
+                opcode,
+                text: disassemble(opcode),
+            }
+        })
+        .collect()
+}
+
+// Uses the mnemonics of Cowgod's technical reference
+pub fn disassemble(opcode: u16) -> String {
+    let nibbles = (
+        ((opcode & 0xF000) >> 12) as u8,
+        ((opcode & 0x0F00) >> 8) as u8,
+        ((opcode & 0x00F0) >> 4) as u8,
+        (opcode & 0x000F) as u8,
+    );
+
+    let nnn = opcode & 0x0FFF;
+    let kk = opcode & 0x00FF;
+
+    match nibbles {
+        (0x0, 0x0, 0xE, 0x0) => "CLS".to_string(),
+        (0x0, 0x0, 0xE, 0xE) => "RET".to_string(),
+        (0x1, _, _, _) => format!("JP 0x{:03X}", nnn),
+        (0x2, _, _, _) => format!("CALL 0x{:03X}", nnn),
+        (0x3, x, _, _) => format!("SE V{:X}, 0x{:02X}", x, kk),
+        (0x4, x, _, _) => format!("SNE V{:X}, 0x{:02X}", x, kk),
+        (0x5, x, y, 0x0) => format!("SE V{:X}, V{:X}", x, y),
+        (0x6, x, _, _) => format!("LD V{:X}, 0x{:02X}", x, kk),
+        (0x7, x, _, _) => format!("ADD V{:X}, 0x{:02X}", x, kk),
+        (0x8, x, y, 0x0) => format!("LD V{:X}, V{:X}", x, y),
+        (0x8, x, y, 0x1) => format!("OR V{:X}, V{:X}", x, y),
+        (0x8, x, y, 0x2) => format!("AND V{:X}, V{:X}", x, y),
+        (0x8, x, y, 0x3) => format!("XOR V{:X}, V{:X}", x, y),
+        (0x8, x, y, 0x4) => format!("ADD V{:X}, V{:X}", x, y),
+        (0x8, x, y, 0x5) => format!("SUB V{:X}, V{:X}", x, y),
+        (0x8, x, y, 0x6) => format!("SHR V{:X}, V{:X}", x, y),
+        (0x8, x, y, 0x7) => format!("SUBN V{:X}, V{:X}", x, y),
+        (0x8, x, y, 0xE) => format!("SHL V{:X}, V{:X}", x, y),
+        (0x9, x, y, 0x0) => format!("SNE V{:X}, V{:X}", x, y),
+        (0xA, _, _, _) => format!("LD I, 0x{:03X}", nnn),
+        (0xB, _, _, _) => format!("JP V0, 0x{:03X}", nnn),
+        (0xC, x, _, _) => format!("RND V{:X}, 0x{:02X}", x, kk),
+        (0xD, x, y, n) => format!("DRW V{:X}, V{:X}, {}", x, y, n),
+        (0xE, x, 0x9, 0xE) => format!("SKP V{:X}", x),
+        (0xE, x, 0xA, 0x1) => format!("SKNP V{:X}", x),
+        (0xF, x, 0x0, 0x7) => format!("LD V{:X}, DT", x),
+        (0xF, x, 0x0, 0xA) => format!("LD V{:X}, K", x),
+        (0xF, x, 0x1, 0x5) => format!("LD DT, V{:X}", x),
+        (0xF, x, 0x1, 0x8) => format!("LD ST, V{:X}", x),
+        (0xF, x, 0x1, 0xE) => format!("ADD I, V{:X}", x),
+        (0xF, x, 0x2, 0x9) => format!("LD F, V{:X}", x),
+        (0xF, x, 0x3, 0x3) => format!("LD B, V{:X}", x),
+        (0xF, x, 0x5, 0x5) => format!("LD [I], V{:X}", x),
+        (0xF, x, 0x6, 0x5) => format!("LD V{:X}, [I]", x),
+        _ => format!("DW 0x{:04X}", opcode),
+    }
+}
@@ -1,8 +1,33 @@
+use crate::palette::{Palette, Rgb};
+
 pub const WIDTH: usize = 64;
 pub const HEIGHT: usize = 32;
 
+// The most significant bit is the leftmost pixel of the row
+pub type Row = u64;
+
+// One byte per pixel for each possible byte of a row
+const UNPACKED_BYTES: [[u8; 8]; 256] = {
+    let mut table = [[0; 8]; 256];
+    let mut byte = 0;
+
+    while byte < 256 {
+        let mut bit = 0;
+
+        while bit < 8 {
+            table[byte][bit] = (byte >> (7 - bit) & 1) as u8;
+            bit += 1;
+        }
+
+        byte += 1;
+    }
+
+    table
+};
+
 #[derive(Debug)]
 pub struct Display {
+    rows: [Row; HEIGHT],
     buffer: [u8; WIDTH * HEIGHT],
     updated: bool,
 }
@@ -10,6 +35,7 @@ pub struct Display {
 impl Default for Display {
     fn default() -> Self {
         Self {
+            rows: [0; HEIGHT],
             buffer: [0; WIDTH * HEIGHT],
             updated: false,
         }
@@ -17,23 +43,33 @@ impl Default for Display {
 }
 
 impl Display {
-    pub fn load_sprite(&mut self, x: usize, y: usize, sprite: &[u8]) -> bool {
+    // The starting position always wraps around while the sprite itself is
+    // either clipped or wrapped at the edges
+    pub fn load_sprite(&mut self, x: usize, y: usize, sprite: &[u8], clip: bool) -> bool {
         let mut collision = false;
+        let x = x % WIDTH;
+        let y = y % HEIGHT;
 
-        for (i, row) in sprite.iter().enumerate() {
-            for offset in 0..8 {
-                let new = row >> (7 - offset) & 1;
-                let py = (y + i) % HEIGHT;
-                let px = (x + offset) % WIDTH;
-                let index = (py * WIDTH) + px;
-                let old = self.buffer[index];
+        for (i, &byte) in sprite.iter().enumerate() {
+            if clip && y + i >= HEIGHT {
+                break;
+            }
 
-                self.buffer[index] = new ^ old;
+            let py = (y + i) % HEIGHT;
+            let row = (byte as Row) << (Row::BITS - 8);
+            let mask = match clip {
+                true => row >> x,
+                false => row.rotate_right(x as u32),
+            };
+            let old = self.rows[py];
 
-                if !collision && new == 1 && new ^ old == 0 {
-                    collision = true;
-                }
+            if mask == 0 {
+                continue;
             }
+
+            collision |= old & mask != 0;
+            self.rows[py] = old ^ mask;
+            self.unpack_sprite_row(x, py, clip);
         }
 
         self.updated = true;
@@ -42,13 +78,32 @@ impl Display {
     }
 
     pub fn clear(&mut self) {
+        self.rows = [0; HEIGHT];
         self.buffer = [0; WIDTH * HEIGHT];
     }
 
+    pub fn get_rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    pub fn set_rows(&mut self, rows: &[Row]) {
+        self.rows.copy_from_slice(rows);
+
+        for y in 0..HEIGHT {
+            self.unpack_row(y);
+        }
+
+        self.updated = true;
+    }
+
     pub fn get_buffer(&self) -> &[u8] {
         &self.buffer
     }
 
+    pub fn render_rgba(&self, palette: &Palette, scale: usize) -> Vec<u8> {
+        render_rgba(&self.buffer, scale, |pixel| palette.get_color(pixel))
+    }
+
     pub fn has_changed(&self) -> bool {
         self.updated
     }
@@ -56,4 +111,61 @@ impl Display {
     pub fn clear_status(&mut self) {
         self.updated = false
     }
+
+    // Only the eight pixels under the sprite are written to the unpacked
+    // buffer, in one copy unless the sprite crosses the edge
+    fn unpack_sprite_row(&mut self, x: usize, y: usize, clip: bool) {
+        let row = self.rows[y];
+        let start = y * WIDTH;
+
+        if x + 8 <= WIDTH {
+            let byte = (row >> (WIDTH - 8 - x)) as u8;
+            self.buffer[start + x..start + x + 8].copy_from_slice(&UNPACKED_BYTES[byte as usize]);
+            return;
+        }
+
+        for offset in 0..8 {
+            let px = match (x + offset, clip) {
+                (px, _) if px < WIDTH => px,
+                (_, true) => break,
+                (px, false) => px - WIDTH,
+            };
+
+            self.buffer[start + px] = (row >> (WIDTH - 1 - px) & 1) as u8;
+        }
+    }
+
+    fn unpack_row(&mut self, y: usize) {
+        let row = self.rows[y];
+        let start = y * WIDTH;
+
+        for (x, pixel) in self.buffer[start..start + WIDTH].iter_mut().enumerate() {
+            *pixel = (row >> (WIDTH - 1 - x) & 1) as u8;
+        }
+    }
+}
+
+// Four bytes per pixel, each cell of the buffer becomes a square of pixels
+pub(crate) fn render_rgba<F>(buffer: &[u8], scale: usize, color: F) -> Vec<u8>
+where
+    F: Fn(u8) -> Rgb,
+{
+    let scale = scale.max(1);
+    let mut rgba = Vec::with_capacity(buffer.len() * scale * scale * 4);
+
+    for row in buffer.chunks(WIDTH) {
+        let line = row
+            .iter()
+            .flat_map(|&cell| {
+                let color = color(cell);
+                [color.r, color.g, color.b, u8::MAX].repeat(scale)
+            })
+            .collect::<Vec<_>>();
+
+        for _ in 0..scale {
+            rgba.extend_from_slice(&line);
+        }
+    }
+
+    rgba
 }
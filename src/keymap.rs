@@ -0,0 +1,117 @@
+// Keyboard keys bound to each CHIP-8 key, from 0x0 to 0xF
+
+#[rustfmt::skip]
+const QWERTY: [&str; 16] = [
+    "x", "1", "2", "3", "q", "w", "e", "a",
+    "s", "d", "z", "c", "4", "r", "f", "v",
+];
+
+#[rustfmt::skip]
+const AZERTY: [&str; 16] = [
+    "x", "&", "é", "\"", "a", "z", "e", "q",
+    "s", "d", "w", "c", "'", "r", "f", "v",
+];
+
+#[rustfmt::skip]
+const DVORAK: [&str; 16] = [
+    "q", "1", "2", "3", "'", ",", ".", "a",
+    "o", "e", ";", "j", "4", "p", "u", "k",
+];
+
+#[rustfmt::skip]
+const NUMPAD: [&str; 16] = [
+    "0", "1", "2", "3", "4", "5", "6", "7",
+    "8", "9", "/", "*", "-", "+", "enter", ".",
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    #[default]
+    Qwerty,
+    Azerty,
+    Dvorak,
+    Numpad,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Preset::Qwerty,
+        Preset::Azerty,
+        Preset::Dvorak,
+        Preset::Numpad,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Qwerty => "qwerty",
+            Preset::Azerty => "azerty",
+            Preset::Dvorak => "dvorak",
+            Preset::Numpad => "numpad",
+        }
+    }
+
+    fn keys(&self) -> [&'static str; 16] {
+        match self {
+            Preset::Qwerty => QWERTY,
+            Preset::Azerty => AZERTY,
+            Preset::Dvorak => DVORAK,
+            Preset::Numpad => NUMPAD,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    keys: [String; 16],
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_preset(Preset::default())
+    }
+}
+
+impl Keymap {
+    pub fn from_preset(preset: Preset) -> Self {
+        Self {
+            keys: preset.keys().map(String::from),
+        }
+    }
+
+    pub fn set_key(&mut self, key: u8, name: &str) {
+        self.keys[key as usize] = normalize(name);
+    }
+
+    pub fn get_key(&self, name: &str) -> Option<u8> {
+        let name = normalize(name);
+
+        self.keys
+            .iter()
+            .position(|key| *key == name)
+            .map(|pos| pos as u8)
+    }
+
+    pub fn get_name(&self, key: u8) -> &str {
+        &self.keys[key as usize]
+    }
+}
+
+// Converts key names from the different frontends to a common form,
+// e.g. "ArrowUp" (web) and "Up" (terminal) both become "up"
+pub fn normalize(name: &str) -> String {
+    let name = name.to_lowercase();
+    let name = match name.as_str() {
+        " " => "space",
+        "escape" => "esc",
+        "return" => "enter",
+        name => name.strip_prefix("arrow").unwrap_or(name),
+    };
+
+    name.to_string()
+}
@@ -1,7 +1,22 @@
+pub mod assembler;
+pub mod audio;
 pub mod chip;
+pub mod database;
+pub mod disassembler;
 pub mod display;
 pub mod keyboard;
+pub mod keymap;
 pub mod memory;
+pub mod octo;
+pub mod palette;
+pub mod phosphor;
+pub mod platform;
+pub mod quirks;
+pub mod recorder;
+pub mod rom;
+pub mod screenshot;
+pub mod share;
+pub mod state;
 
 #[cfg(feature = "wasm")]
 pub mod wasm;
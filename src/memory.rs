@@ -1,4 +1,4 @@
-const MEM_SIZE: usize = 4096;
+pub const MEM_SIZE: usize = 4096;
 
 const FONTS: [u8; 80] = [
     0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
@@ -38,6 +38,10 @@ impl Memory {
         self.memory[FONTS.len()..].fill(0);
     }
 
+    pub fn get_buffer(&self) -> &[u8] {
+        &self.memory
+    }
+
     pub fn read(&self, addr: u16) -> u8 {
         self.memory[addr as usize]
     }
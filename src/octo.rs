@@ -0,0 +1,107 @@
+use crate::{
+    assembler,
+    palette::{Palette, Rgb},
+    quirks::Quirks,
+};
+use serde::Deserialize;
+use std::io::{Error, ErrorKind, Read};
+
+// Options saved by Octo along with the program
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OctoOptions {
+    pub tickrate: Option<u32>,
+    pub background_color: Option<String>,
+    pub fill_color: Option<String>,
+    pub fill_color2: Option<String>,
+    pub blend_color: Option<String>,
+    pub shift_quirks: Option<bool>,
+    pub load_store_quirks: Option<bool>,
+    pub jump_quirks: Option<bool>,
+    pub logic_quirks: Option<bool>,
+    pub clip_quirks: Option<bool>,
+}
+
+impl OctoOptions {
+    pub fn get_quirks(&self, base: Quirks) -> Quirks {
+        Quirks {
+            vf_reset: self.logic_quirks.unwrap_or(base.vf_reset),
+            // the load/store quirk leaves I unchanged
+            memory: self.load_store_quirks.map_or(base.memory, |quirk| !quirk),
+            shifting: self.shift_quirks.unwrap_or(base.shifting),
+            jumping: self.jump_quirks.unwrap_or(base.jumping),
+            clipping: self.clip_quirks.unwrap_or(base.clipping),
+        }
+    }
+
+    pub fn get_palette(&self, base: Palette) -> Palette {
+        let colors = [
+            &self.background_color,
+            &self.fill_color,
+            &self.fill_color2,
+            &self.blend_color,
+        ];
+        let mut palette = base;
+
+        for (index, color) in colors.into_iter().enumerate() {
+            if let Some(color) = color.as_deref().and_then(Rgb::from_hex) {
+                palette.colors[index] = color;
+            }
+        }
+
+        palette
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Cartridge {
+    // Octo source code
+    pub program: String,
+    #[serde(default)]
+    pub options: OctoOptions,
+}
+
+impl Cartridge {
+    // The payload is stored in the two low bits of each pixel of the GIF
+    // frames, starting with a 32-bit big endian length followed by a JSON
+    // object with the program and the options
+    pub fn decode<R: Read>(reader: R) -> Result<Self, Error> {
+        let mut options = gif::DecodeOptions::new();
+
+        options.set_color_output(gif::ColorOutput::Indexed);
+
+        let mut decoder = options.read_info(reader).map_err(invalid_cartridge)?;
+        let mut pixels = Vec::new();
+
+        while let Some(frame) = decoder.read_next_frame().map_err(invalid_cartridge)? {
+            pixels.extend_from_slice(&frame.buffer);
+        }
+
+        let mut bytes = pixels.chunks_exact(4).map(|chunk| {
+            chunk
+                .iter()
+                .fold(0, |byte, pixel| (byte << 2) | (pixel & 3))
+        });
+        let length = bytes
+            .by_ref()
+            .take(4)
+            .fold(0, |length, byte| (length << 8) | byte as usize);
+        let payload = bytes.take(length).collect::<Vec<_>>();
+
+        if payload.len() < length {
+            return Err(invalid_cartridge("truncated payload"));
+        }
+
+        serde_json::from_slice(&payload).map_err(invalid_cartridge)
+    }
+
+    // Octo programs always start at 0x200
+    pub fn assemble(&self) -> Result<Vec<u8>, Error> {
+        assembler::assemble(&self.program)
+    }
+}
+
+fn invalid_cartridge<E: ToString>(error: E) -> Error {
+    let message = format!("Invalid Octo cartridge: {}", error.to_string());
+    Error::new(ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,145 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    // Parses colors in the #RRGGBB form
+    pub fn from_hex(value: &str) -> Option<Self> {
+        let hex = value.strip_prefix('#')?;
+
+        // from_str_radix would also accept a sign
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    // Linear interpolation towards the other color, 255 gives the other color
+    pub fn blend(&self, other: Rgb, amount: u8) -> Self {
+        let mix = |from: u8, to: u8| {
+            let (from, to, amount) = (from as u32, to as u32, amount as u32);
+            ((from * (255 - amount) + to * amount) / 255) as u8
+        };
+
+        Self::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+
+    pub fn luma(&self) -> u8 {
+        let (r, g, b) = (self.r as u32, self.g as u32, self.b as u32);
+
+        ((r * 299 + g * 587 + b * 114) / 1000) as u8
+    }
+}
+
+// Colors are indexed by the pixel value, which leaves room for the two bit
+// planes of XO-CHIP: background, first plane, second plane and both planes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub colors: [Rgb; 4],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(Rgb::new(255, 255, 255), Rgb::new(0, 0, 0))
+    }
+}
+
+impl Palette {
+    pub fn new(fg: Rgb, bg: Rgb) -> Self {
+        Self {
+            colors: [bg, fg, fg, fg],
+        }
+    }
+
+    pub fn fg(&self) -> Rgb {
+        self.colors[1]
+    }
+
+    pub fn bg(&self) -> Rgb {
+        self.colors[0]
+    }
+
+    pub fn set_fg(&mut self, color: Rgb) {
+        self.colors[1] = color;
+    }
+
+    pub fn set_bg(&mut self, color: Rgb) {
+        self.colors[0] = color;
+    }
+
+    pub fn get_color(&self, pixel: u8) -> Rgb {
+        self.colors[(pixel & 0b11) as usize]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Amber,
+    GreenPhosphor,
+    Gameboy,
+    HighContrast,
+    Octo,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 5] = [
+        Theme::Amber,
+        Theme::GreenPhosphor,
+        Theme::Gameboy,
+        Theme::HighContrast,
+        Theme::Octo,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.replace('_', "-");
+
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name().eq_ignore_ascii_case(&name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Amber => "amber",
+            Theme::GreenPhosphor => "green-phosphor",
+            Theme::Gameboy => "gameboy",
+            Theme::HighContrast => "high-contrast",
+            Theme::Octo => "octo",
+        }
+    }
+
+    pub fn palette(&self) -> Palette {
+        let colors = match self {
+            Theme::Amber => [0x1A0F00, 0xFFB000, 0xB36B00, 0xFFE0A0],
+            Theme::GreenPhosphor => [0x0A140A, 0x33FF33, 0x1A991A, 0xB3FFB3],
+            Theme::Gameboy => [0x9BBC0F, 0x0F380F, 0x8BAC0F, 0x306230],
+            Theme::HighContrast => [0x000000, 0xFFFFFF, 0xFFFF00, 0x00FFFF],
+            Theme::Octo => [0x996600, 0xFFCC00, 0xFF6600, 0x662200],
+        };
+
+        Palette {
+            colors: colors.map(|color: u32| {
+                let [_, r, g, b] = color.to_be_bytes();
+                Rgb::new(r, g, b)
+            }),
+        }
+    }
+}
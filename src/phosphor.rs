@@ -0,0 +1,57 @@
+use crate::{
+    display::{self, Display, HEIGHT, WIDTH},
+    palette::Palette,
+};
+
+pub const DEFAULT_DECAY: u8 = 64;
+
+#[derive(Debug)]
+pub struct Phosphor {
+    buffer: [u8; WIDTH * HEIGHT],
+    decay: u8,
+}
+
+impl Default for Phosphor {
+    fn default() -> Self {
+        Self::new(DEFAULT_DECAY)
+    }
+}
+
+impl Phosphor {
+    pub fn new(decay: u8) -> Self {
+        Self {
+            buffer: [0; WIDTH * HEIGHT],
+            decay: decay.max(1),
+        }
+    }
+
+    pub fn set_decay(&mut self, decay: u8) {
+        self.decay = decay.max(1);
+    }
+
+    // Should be called once per frame, lit pixels are set to full intensity
+    // while the others fade out by the decay rate
+    pub fn update(&mut self, display: &Display) {
+        for (level, &pixel) in self.buffer.iter_mut().zip(display.get_buffer()) {
+            *level = match pixel {
+                1 => u8::MAX,
+                _ => level.saturating_sub(self.decay),
+            };
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer = [0; WIDTH * HEIGHT];
+    }
+
+    pub fn get_buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    // Fading pixels go from the foreground to the background color
+    pub fn render_rgba(&self, palette: &Palette, scale: usize) -> Vec<u8> {
+        display::render_rgba(&self.buffer, scale, |level| {
+            palette.bg().blend(palette.fg(), level)
+        })
+    }
+}
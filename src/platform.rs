@@ -0,0 +1,109 @@
+use crate::quirks::Quirks;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Platform {
+    #[default]
+    OriginalChip8,
+    HybridVip,
+    ModernChip8,
+    Chip48,
+    SuperChip1,
+    SuperChip,
+    XoChip,
+}
+
+impl Platform {
+    pub const ALL: [Platform; 7] = [
+        Platform::OriginalChip8,
+        Platform::HybridVip,
+        Platform::ModernChip8,
+        Platform::Chip48,
+        Platform::SuperChip1,
+        Platform::SuperChip,
+        Platform::XoChip,
+    ];
+
+    // Identifiers used by the CHIP-8 database
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|platform| platform.id().eq_ignore_ascii_case(id))
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Platform::OriginalChip8 => "originalChip8",
+            Platform::HybridVip => "hybridVIP",
+            Platform::ModernChip8 => "modernChip8",
+            Platform::Chip48 => "chip48",
+            Platform::SuperChip1 => "superchip1",
+            Platform::SuperChip => "superchip",
+            Platform::XoChip => "xochip",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "ch8" => Some(Platform::OriginalChip8),
+            "sc8" => Some(Platform::SuperChip),
+            "xo8" => Some(Platform::XoChip),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::OriginalChip8 => "CHIP-8",
+            Platform::HybridVip => "CHIP-8 (hybrid VIP)",
+            Platform::ModernChip8 => "CHIP-8 (modern)",
+            Platform::Chip48 => "CHIP-48",
+            Platform::SuperChip1 => "SUPER-CHIP 1.1",
+            Platform::SuperChip => "SUPER-CHIP (modern)",
+            Platform::XoChip => "XO-CHIP",
+        }
+    }
+
+    // Instructions per frame
+    pub fn default_tickrate(&self) -> u32 {
+        match self {
+            Platform::OriginalChip8 | Platform::HybridVip => 15,
+            Platform::ModernChip8 => 12,
+            Platform::Chip48 | Platform::SuperChip1 | Platform::SuperChip => 30,
+            Platform::XoChip => 100,
+        }
+    }
+
+    pub fn quirks(&self) -> Quirks {
+        match self {
+            Platform::OriginalChip8 | Platform::HybridVip => Quirks {
+                vf_reset: true,
+                memory: true,
+                shifting: false,
+                jumping: false,
+                clipping: true,
+            },
+            Platform::ModernChip8 => Quirks {
+                vf_reset: false,
+                memory: true,
+                shifting: false,
+                jumping: false,
+                clipping: true,
+            },
+            // CHIP-48 increments I by X which is approximated by leaving it unchanged
+            Platform::Chip48 | Platform::SuperChip1 | Platform::SuperChip => Quirks {
+                vf_reset: false,
+                memory: false,
+                shifting: true,
+                jumping: true,
+                clipping: true,
+            },
+            Platform::XoChip => Quirks {
+                vf_reset: false,
+                memory: true,
+                shifting: false,
+                jumping: false,
+                clipping: false,
+            },
+        }
+    }
+}
@@ -0,0 +1,56 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    // AND, OR and XOR reset VF to zero
+    pub vf_reset: bool,
+    // FX55 and FX65 increment I
+    pub memory: bool,
+    // 8XY6 and 8XYE shift VX in place instead of VY
+    pub shifting: bool,
+    // BNNN jumps to NNN + VX instead of NNN + V0
+    pub jumping: bool,
+    // Sprites are clipped at the edges of the screen instead of wrapping
+    pub clipping: bool,
+}
+
+impl Quirks {
+    pub const NAMES: [&'static str; 5] = ["vf_reset", "memory", "shifting", "jumping", "clipping"];
+
+    pub fn get(&self, name: &str) -> Option<bool> {
+        match name {
+            "vf_reset" => Some(self.vf_reset),
+            "memory" => Some(self.memory),
+            "shifting" => Some(self.shifting),
+            "jumping" => Some(self.jumping),
+            "clipping" => Some(self.clipping),
+            _ => None,
+        }
+    }
+
+    // Returns false for unknown names
+    pub fn set(&mut self, name: &str, value: bool) -> bool {
+        let quirk = match name {
+            "vf_reset" => &mut self.vf_reset,
+            "memory" => &mut self.memory,
+            "shifting" => &mut self.shifting,
+            "jumping" => &mut self.jumping,
+            "clipping" => &mut self.clipping,
+            _ => return false,
+        };
+
+        *quirk = value;
+
+        true
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Self {
+            vf_reset: false,
+            memory: false,
+            shifting: true,
+            jumping: false,
+            clipping: false,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    display::{HEIGHT, WIDTH},
+    palette::Palette,
+    screenshot::Screenshot,
+};
+use gif::{Encoder, Frame, Repeat};
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+// Each emulated frame lasts a tick of the 60 Hz timers
+pub const FRAME_RATE: u32 = 60;
+
+// Most viewers slow down frames with a delay below 20ms, such frames are
+// dropped instead to keep the playback speed
+const MIN_DELAY: u64 = 2;
+
+pub struct GifRecorder<W: Write> {
+    encoder: Encoder<W>,
+    scale: usize,
+    width: u16,
+    height: u16,
+    pending: Option<Vec<u8>>,
+    frame_count: u64,
+    elapsed: u64,
+}
+
+impl<W: Write> GifRecorder<W> {
+    pub fn new(writer: W, palette: Palette, scale: usize) -> io::Result<Self> {
+        let scale = scale.max(1);
+        let colors = palette
+            .colors
+            .iter()
+            .flat_map(|color| [color.r, color.g, color.b])
+            .collect::<Vec<_>>();
+        // GIF dimensions are 16-bit
+        let size = |pixels: usize| {
+            pixels
+                .checked_mul(scale)
+                .and_then(|size| u16::try_from(size).ok())
+                .ok_or_else(|| io::Error::other(format!("Recording too large at scale {}", scale)))
+        };
+        let (width, height) = (size(WIDTH)?, size(HEIGHT)?);
+        let mut encoder = Encoder::new(writer, width, height, &colors).map_err(io::Error::other)?;
+
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(io::Error::other)?;
+
+        Ok(Self {
+            encoder,
+            scale,
+            width,
+            height,
+            pending: None,
+            frame_count: 0,
+            elapsed: 0,
+        })
+    }
+
+    // Should be called once per frame, consecutive identical frames are
+    // merged into a single one with a longer delay
+    pub fn add_frame(&mut self, buffer: &[u8]) -> io::Result<()> {
+        if self.pending.as_deref() != Some(buffer) {
+            self.flush(false)?;
+            self.pending = Some(buffer.to_vec());
+        }
+
+        self.frame_count += 1;
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush(true)?;
+        self.encoder.into_inner().map_err(io::Error::other)
+    }
+
+    fn flush(&mut self, last: bool) -> io::Result<()> {
+        let Some(buffer) = self.pending.take() else {
+            return Ok(());
+        };
+
+        // delays are computed from the total elapsed time to avoid drifting
+        let target = self.frame_count * 100 / FRAME_RATE as u64;
+        let delay = target.saturating_sub(self.elapsed);
+
+        if delay < MIN_DELAY && !last {
+            return Ok(());
+        }
+
+        let delay = delay.clamp(MIN_DELAY, u16::MAX as u64);
+        let pixels = Screenshot::new(&buffer)
+            .with_scale(self.scale)
+            .scaled_pixels()
+            .map(|pixel| pixel & 0b11)
+            .collect::<Vec<_>>();
+        let frame = Frame {
+            width: self.width,
+            height: self.height,
+            delay: delay as u16,
+            buffer: Cow::Owned(pixels),
+            ..Default::default()
+        };
+
+        self.elapsed += delay;
+        self.encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+}
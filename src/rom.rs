@@ -0,0 +1,35 @@
+use crate::memory::MEM_SIZE;
+use std::io::{Error, ErrorKind};
+
+pub const DEFAULT_ADDRESS: u16 = 0x200;
+// ETI-660 programs are loaded after its larger interpreter
+pub const ETI_660_ADDRESS: u16 = 0x600;
+
+pub fn get_hash(program: &[u8]) -> String {
+    sha1_smol::Sha1::from(program).digest().to_string()
+}
+
+pub fn get_max_size(address: u16) -> usize {
+    MEM_SIZE.saturating_sub(address as usize)
+}
+
+pub fn validate(program: &[u8], address: u16) -> Result<(), Error> {
+    if address < DEFAULT_ADDRESS || address as usize >= MEM_SIZE {
+        let message = format!("Invalid load address: 0x{:03X}", address);
+        return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let max_size = get_max_size(address);
+
+    if program.len() > max_size {
+        let message = format!(
+            "ROM too large for platform: {} bytes, the maximum is {} bytes at 0x{:03X}",
+            program.len(),
+            max_size,
+            address
+        );
+        return Err(Error::new(ErrorKind::InvalidData, message));
+    }
+
+    Ok(())
+}
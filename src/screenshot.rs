@@ -0,0 +1,187 @@
+use crate::{
+    display::{HEIGHT, WIDTH},
+    palette::Palette,
+};
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+// Plain PBM and PGM files should not have lines longer than 70 characters
+const MAX_LINE_LENGTH: usize = 70;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Pbm,
+    Pgm,
+    Ascii,
+}
+
+impl Format {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "png" => Some(Format::Png),
+            "pbm" => Some(Format::Pbm),
+            "pgm" => Some(Format::Pgm),
+            "txt" => Some(Format::Ascii),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Screenshot<'a> {
+    buffer: &'a [u8],
+    scale: usize,
+    palette: Palette,
+    symbols: (char, char),
+}
+
+impl<'a> Screenshot<'a> {
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self {
+            buffer,
+            scale: 1,
+            palette: Palette::default(),
+            symbols: ('#', '.'),
+        }
+    }
+
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    // Characters used for lit and unlit pixels in the ASCII output
+    pub fn with_symbols(mut self, on: char, off: char) -> Self {
+        self.symbols = (on, off);
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        WIDTH * self.scale
+    }
+
+    pub fn height(&self) -> usize {
+        HEIGHT * self.scale
+    }
+
+    pub fn write<W: Write>(&self, format: Format, writer: W) -> io::Result<()> {
+        match format {
+            Format::Png => self.write_png(writer),
+            Format::Pbm => self.write_pbm(writer),
+            Format::Pgm => self.write_pgm(writer),
+            Format::Ascii => self.write_ascii(writer),
+        }
+    }
+
+    pub fn encode(&self, format: Format) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        // writing to a vector never fails
+        self.write(format, &mut bytes).unwrap();
+        bytes
+    }
+
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width() as u32, self.height() as u32);
+
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data = self
+            .scaled_pixels()
+            .flat_map(|pixel| {
+                let color = self.palette.get_color(pixel);
+                [color.r, color.g, color.b]
+            })
+            .collect::<Vec<_>>();
+
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+
+        writer.write_image_data(&data).map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+
+    pub fn write_pbm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "P1")?;
+        writeln!(writer, "{} {}", self.width(), self.height())?;
+
+        self.write_rows(writer, "", Some(MAX_LINE_LENGTH), |pixel| {
+            if pixel == 0 { "0" } else { "1" }.into()
+        })
+    }
+
+    pub fn write_pgm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "P2")?;
+        writeln!(writer, "{} {}", self.width(), self.height())?;
+        writeln!(writer, "{}", u8::MAX)?;
+
+        self.write_rows(writer, " ", Some(MAX_LINE_LENGTH), |pixel| {
+            self.palette.get_color(pixel).luma().to_string()
+        })
+    }
+
+    pub fn write_ascii<W: Write>(&self, writer: W) -> io::Result<()> {
+        let (on, off) = self.symbols;
+
+        self.write_rows(writer, "", None, |pixel| {
+            if pixel == 0 { off } else { on }.to_string()
+        })
+    }
+
+    // Each row starts on a new line, long rows are wrapped when a maximum
+    // length is given
+    fn write_rows<W, F>(
+        &self,
+        mut writer: W,
+        separator: &str,
+        max_length: Option<usize>,
+        cell: F,
+    ) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(u8) -> String,
+    {
+        let pixels = self.scaled_pixels().collect::<Vec<_>>();
+        let max_length = max_length.unwrap_or(usize::MAX);
+
+        for row in pixels.chunks(self.width()) {
+            let mut line = String::new();
+
+            for &pixel in row {
+                let value = cell(pixel);
+
+                if !line.is_empty() && line.len() + separator.len() + value.len() > max_length {
+                    writeln!(writer, "{}", line)?;
+                    line.clear();
+                } else if !line.is_empty() {
+                    line.push_str(separator);
+                }
+
+                line.push_str(&value);
+            }
+
+            writeln!(writer, "{}", line)?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn scaled_pixels(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.height()).flat_map(move |y| {
+            (0..self.width()).map(move |x| {
+                let index = (y / self.scale) * WIDTH + (x / self.scale);
+                self.buffer[index]
+            })
+        })
+    }
+}
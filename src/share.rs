@@ -0,0 +1,133 @@
+use crate::{
+    memory::MEM_SIZE,
+    palette::{Palette, Rgb},
+    quirks::Quirks,
+    rom::{self, DEFAULT_ADDRESS},
+    state::{decode_quirks, encode_quirks},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use miniz_oxide::{deflate, inflate};
+use std::io::{Error, ErrorKind};
+
+const VERSION: u8 = 1;
+const HAS_TICKRATE: u8 = 1 << 0;
+const HAS_PALETTE: u8 = 1 << 1;
+// Version, flags, quirks, address, tickrate and two colors
+const MAX_HEADER_SIZE: usize = 13;
+const COMPRESSION_LEVEL: u8 = 10;
+
+// A program and the settings needed to run it, packed into a string that
+// fits in the fragment of a URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub program: Vec<u8>,
+    pub address: u16,
+    pub quirks: Quirks,
+    pub tickrate: Option<u16>,
+    pub palette: Option<Palette>,
+}
+
+impl Share {
+    pub fn new(program: &[u8]) -> Self {
+        Self {
+            program: program.to_vec(),
+            address: DEFAULT_ADDRESS,
+            quirks: Quirks::default(),
+            tickrate: None,
+            palette: None,
+        }
+    }
+
+    // The fields are deflated then encoded in unpadded base64url
+    pub fn encode(&self) -> String {
+        let mut bytes = vec![VERSION, 0, encode_quirks(self.quirks)];
+
+        bytes.extend_from_slice(&self.address.to_be_bytes());
+
+        if let Some(tickrate) = self.tickrate {
+            bytes[1] |= HAS_TICKRATE;
+            bytes.extend_from_slice(&tickrate.to_be_bytes());
+        }
+
+        if let Some(palette) = &self.palette {
+            bytes[1] |= HAS_PALETTE;
+
+            for color in [palette.bg(), palette.fg()] {
+                bytes.extend_from_slice(&[color.r, color.g, color.b]);
+            }
+        }
+
+        bytes.extend_from_slice(&self.program);
+
+        URL_SAFE_NO_PAD.encode(deflate::compress_to_vec(&bytes, COMPRESSION_LEVEL))
+    }
+
+    // Accepts the code alone or a whole URL ending with it as fragment
+    pub fn decode(text: &str) -> Result<Self, Error> {
+        let code = text.rsplit_once('#').map_or(text, |(_, code)| code).trim();
+        let compressed = URL_SAFE_NO_PAD
+            .decode(code)
+            .map_err(|_| invalid_code("not base64url"))?;
+        let bytes = inflate::decompress_to_vec_with_limit(&compressed, MAX_HEADER_SIZE + MEM_SIZE)
+            .map_err(|_| invalid_code("corrupted or too large data"))?;
+
+        let (header, mut rest) = split(&bytes, 5)?;
+
+        if header[0] != VERSION {
+            return Err(invalid_code(format!("unsupported version {}", header[0])));
+        }
+
+        let flags = header[1];
+        let quirks = decode_quirks(header[2]);
+        let address = u16::from_be_bytes([header[3], header[4]]);
+        let mut tickrate = None;
+        let mut palette = None;
+
+        if flags & HAS_TICKRATE != 0 {
+            let (value, tail) = split(rest, 2)?;
+            let value = u16::from_be_bytes([value[0], value[1]]);
+
+            if value == 0 {
+                return Err(invalid_code("null tickrate"));
+            }
+
+            tickrate = Some(value);
+            rest = tail;
+        }
+
+        if flags & HAS_PALETTE != 0 {
+            let (colors, tail) = split(rest, 6)?;
+            let bg = Rgb::new(colors[0], colors[1], colors[2]);
+            let fg = Rgb::new(colors[3], colors[4], colors[5]);
+
+            palette = Some(Palette::new(fg, bg));
+            rest = tail;
+        }
+
+        if rest.is_empty() {
+            return Err(invalid_code("empty program"));
+        }
+
+        rom::validate(rest, address)?;
+
+        Ok(Self {
+            program: rest.to_vec(),
+            address,
+            quirks,
+            tickrate,
+            palette,
+        })
+    }
+}
+
+fn split(bytes: &[u8], length: usize) -> Result<(&[u8], &[u8]), Error> {
+    match bytes.len() < length {
+        true => Err(invalid_code("truncated data")),
+        false => Ok(bytes.split_at(length)),
+    }
+}
+
+fn invalid_code<E: ToString>(error: E) -> Error {
+    let message = format!("Invalid share code: {}", error.to_string());
+    Error::new(ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,179 @@
+use crate::{
+    display::{Row, HEIGHT},
+    memory::MEM_SIZE,
+    quirks::Quirks,
+};
+use std::io::{Error, ErrorKind};
+
+const MAGIC: &[u8; 4] = b"YACE";
+const VERSION: u8 = 1;
+const STACK_SIZE: usize = 16;
+
+// Snapshot of the machine, the program is included so that a restored
+// state can still be reset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub v: [u8; 16],
+    pub i: u16,
+    pub dt: u8,
+    pub st: u8,
+    pub pc: u16,
+    pub stack: Vec<u16>,
+    pub memory: Vec<u8>,
+    pub rows: Vec<Row>,
+    pub quirks: Quirks,
+    pub address: u16,
+    pub program: Vec<u8>,
+}
+
+impl State {
+    // Numbers are stored in big endian after the magic and the version
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.v);
+        bytes.extend_from_slice(&self.i.to_be_bytes());
+        bytes.push(self.dt);
+        bytes.push(self.st);
+        bytes.extend_from_slice(&self.pc.to_be_bytes());
+        bytes.push(self.stack.len() as u8);
+
+        for addr in &self.stack {
+            bytes.extend_from_slice(&addr.to_be_bytes());
+        }
+
+        bytes.extend_from_slice(&self.memory);
+
+        for row in &self.rows {
+            bytes.extend_from_slice(&row.to_be_bytes());
+        }
+
+        bytes.push(encode_quirks(self.quirks));
+        bytes.extend_from_slice(&self.address.to_be_bytes());
+        bytes.extend_from_slice(&(self.program.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.program);
+
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(invalid_state("not a save state"));
+        }
+
+        let version = reader.read_u8()?;
+
+        if version != VERSION {
+            return Err(invalid_state(format!("unsupported version {}", version)));
+        }
+
+        let mut v = [0; 16];
+
+        v.copy_from_slice(reader.take(16)?);
+
+        let i = reader.read_u16()?;
+        let dt = reader.read_u8()?;
+        let st = reader.read_u8()?;
+        let pc = reader.read_u16()?;
+        let sp = reader.read_u8()? as usize;
+
+        if sp > STACK_SIZE {
+            return Err(invalid_state(format!("stack too deep ({} entries)", sp)));
+        }
+
+        let stack = (0..sp)
+            .map(|_| reader.read_u16())
+            .collect::<Result<Vec<_>, _>>()?;
+        let memory = reader.take(MEM_SIZE)?.to_vec();
+        let rows = (0..HEIGHT)
+            .map(|_| reader.read_u64())
+            .collect::<Result<Vec<_>, _>>()?;
+        let quirks = decode_quirks(reader.read_u8()?);
+        let address = reader.read_u16()?;
+        let length = reader.read_u16()? as usize;
+        let program = reader.take(length)?.to_vec();
+
+        Ok(Self {
+            v,
+            i,
+            dt,
+            st,
+            pc,
+            stack,
+            memory,
+            rows,
+            quirks,
+            address,
+            program,
+        })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < length {
+            return Err(invalid_state("truncated data"));
+        }
+
+        let (head, tail) = self.bytes.split_at(length);
+
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Error> {
+        let mut bytes = [0; 8];
+
+        bytes.copy_from_slice(self.take(8)?);
+
+        Ok(u64::from_be_bytes(bytes))
+    }
+}
+
+pub(crate) fn encode_quirks(quirks: Quirks) -> u8 {
+    [
+        quirks.vf_reset,
+        quirks.memory,
+        quirks.shifting,
+        quirks.jumping,
+        quirks.clipping,
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |byte, (bit, &quirk)| byte | ((quirk as u8) << bit))
+}
+
+pub(crate) fn decode_quirks(byte: u8) -> Quirks {
+    let quirk = |bit: u8| byte & (1 << bit) != 0;
+
+    Quirks {
+        vf_reset: quirk(0),
+        memory: quirk(1),
+        shifting: quirk(2),
+        jumping: quirk(3),
+        clipping: quirk(4),
+    }
+}
+
+fn invalid_state<E: ToString>(error: E) -> Error {
+    let message = format!("Invalid save state: {}", error.to_string());
+    Error::new(ErrorKind::InvalidData, message)
+}
@@ -1,9 +1,33 @@
-use crate::chip::Chip;
-use wasm_bindgen::prelude::wasm_bindgen;
+use crate::{
+    audio::Beeper,
+    chip::Chip,
+    disassembler,
+    display::{HEIGHT, WIDTH},
+    keymap::{Keymap, Preset},
+    palette::{Palette, Rgb, Theme},
+    phosphor::Phosphor,
+    platform::Platform,
+    quirks::Quirks,
+    share::Share,
+    state::State,
+};
+use std::{collections::BTreeSet, io::Error};
+use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
+
+const KEY_COUNT: u8 = 16;
+const HEXDUMP_WIDTH: usize = 8;
 
 #[wasm_bindgen]
 pub struct WasmChip {
     chip: Chip,
+    phosphor: Phosphor,
+    palette: Palette,
+    scale: usize,
+    persistence: bool,
+    rgba: Vec<u8>,
+    beeper: Beeper,
+    breakpoints: BTreeSet<u16>,
+    breakpoint_hit: bool,
 }
 
 #[wasm_bindgen]
@@ -12,27 +36,231 @@ impl WasmChip {
     pub fn new() -> Self {
         Self {
             chip: Chip::default(),
+            phosphor: Phosphor::default(),
+            palette: Palette::default(),
+            scale: 1,
+            persistence: false,
+            rgba: Vec::new(),
+            beeper: Beeper::default(),
+            breakpoints: BTreeSet::new(),
+            breakpoint_hit: false,
         }
     }
 
+    // Starts with the quirks of a platform from the CHIP-8 database
+    pub fn with_platform(id: &str) -> Result<WasmChip, JsValue> {
+        let mut chip = Self::new();
+
+        chip.set_platform(id)?;
+
+        Ok(chip)
+    }
+
+    pub fn platforms() -> Vec<String> {
+        Platform::ALL
+            .iter()
+            .map(|platform| platform.id().to_string())
+            .collect()
+    }
+
+    // Platform suggested by extensions like ch8, sc8 or xo8
+    pub fn platform_from_extension(extension: &str) -> Option<String> {
+        Platform::from_extension(extension).map(|platform| platform.id().to_string())
+    }
+
+    pub fn platform_name(id: &str) -> Result<String, JsValue> {
+        Ok(get_platform(id)?.name().to_string())
+    }
+
+    pub fn set_platform(&mut self, id: &str) -> Result<(), JsValue> {
+        self.chip.quirks = get_platform(id)?.quirks();
+        Ok(())
+    }
+
+    pub fn quirks() -> Vec<String> {
+        Quirks::NAMES.iter().map(|name| name.to_string()).collect()
+    }
+
+    pub fn get_quirk(&self, name: &str) -> Result<bool, JsValue> {
+        self.chip
+            .quirks
+            .get(name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown quirk: {}", name)))
+    }
+
+    pub fn set_quirk(&mut self, name: &str, value: bool) -> Result<(), JsValue> {
+        match self.chip.quirks.set(name, value) {
+            true => Ok(()),
+            false => Err(JsValue::from_str(&format!("Unknown quirk: {}", name))),
+        }
+    }
+
+    pub fn save_state(&self) -> Vec<u8> {
+        self.chip.save_state().encode()
+    }
+
+    pub fn load_state(&mut self, state: &[u8]) -> Result<(), JsValue> {
+        let state = State::decode(state).map_err(to_js_error)?;
+
+        self.chip.load_state(&state).map_err(to_js_error)?;
+        self.phosphor.clear();
+
+        Ok(())
+    }
+
+    // Packs the loaded program with its quirks, speed and colors
+    pub fn get_share_code(&self, tickrate: Option<u16>) -> Result<String, JsValue> {
+        let program = self.chip.get_program();
+
+        if program.is_empty() {
+            return Err(JsValue::from_str("No ROM loaded"));
+        }
+
+        let share = Share {
+            program: program.to_vec(),
+            address: self.chip.get_address(),
+            quirks: self.chip.quirks,
+            tickrate,
+            palette: Some(self.palette),
+        };
+
+        Ok(share.encode())
+    }
+
+    // Returns the shared speed, the current program is kept on errors
+    pub fn load_share_code(&mut self, code: &str) -> Result<Option<u16>, JsValue> {
+        let share = Share::decode(code).map_err(to_js_error)?;
+
+        self.chip
+            .load_at(&share.program, share.address)
+            .map_err(to_js_error)?;
+        self.chip.quirks = share.quirks;
+
+        if let Some(palette) = share.palette {
+            self.palette = palette;
+        }
+
+        Ok(share.tickrate)
+    }
+
     pub fn reset(&mut self) {
         self.chip.reset();
+        self.phosphor.clear();
+    }
+
+    pub fn power_cycle(&mut self) {
+        self.chip.power_cycle();
+        self.phosphor.clear();
+    }
+
+    pub fn unload(&mut self) {
+        self.chip.unload();
+        self.phosphor.clear();
+    }
+
+    pub fn load(&mut self, program: &[u8]) -> Result<(), JsValue> {
+        self.chip.load(program).map_err(to_js_error)
+    }
+
+    pub fn load_at(&mut self, program: &[u8], address: u16) -> Result<(), JsValue> {
+        self.chip.load_at(program, address).map_err(to_js_error)
+    }
+
+    pub fn get_hash(&self) -> String {
+        self.chip.get_hash()
+    }
+
+    pub fn get_title(&self) -> Option<String> {
+        self.chip.get_info().map(|info| info.title.clone())
+    }
+
+    pub fn get_platform(&self) -> Option<String> {
+        self.chip
+            .get_info()
+            .map(|info| info.platform.id().to_string())
+    }
+
+    // Key used by the ROM for a hint of the database like "up" or "a"
+    pub fn get_hint_key(&self, hint: &str) -> Option<u8> {
+        self.chip
+            .get_info()?
+            .keys
+            .iter()
+            .find(|(name, _)| name == hint)
+            .map(|(_, key)| *key)
+    }
+
+    pub fn get_tickrate(&self) -> Option<u32> {
+        self.chip.get_info().map(|info| info.tickrate)
+    }
+
+    pub fn get_registers(&self) -> Vec<u8> {
+        self.chip.get_registers().to_vec()
+    }
+
+    pub fn get_i(&self) -> u16 {
+        self.chip.get_i()
+    }
+
+    pub fn get_pc(&self) -> u16 {
+        self.chip.get_pc()
     }
 
-    pub fn load(&mut self, program: &[u8]) {
-        self.chip.load(program);
+    pub fn get_stack(&self) -> Vec<u16> {
+        self.chip.get_stack().to_vec()
     }
 
-    pub fn fetch(&mut self) -> u16 {
-        self.chip.fetch()
+    pub fn get_delay_timer(&self) -> u8 {
+        self.chip.get_delay_timer()
     }
 
-    pub fn execute(&mut self, opcode: u16) {
-        self.chip.execute(opcode);
+    pub fn get_sound_timer(&self) -> u8 {
+        self.chip.get_sound_timer()
     }
 
-    pub fn tick(&mut self) {
-        self.chip.tick();
+    pub fn ptr_memory_buffer(&self) -> *const u8 {
+        self.chip.get_memory().as_ptr()
+    }
+
+    pub fn memory_size(&self) -> usize {
+        self.chip.get_memory().len()
+    }
+
+    pub fn fetch(&mut self) -> Result<u16, JsValue> {
+        self.chip.fetch().map_err(to_js_error)
+    }
+
+    pub fn execute(&mut self, opcode: u16) -> Result<(), JsValue> {
+        self.chip.execute(opcode).map_err(to_js_error)
+    }
+
+    pub fn tick(&mut self) -> Result<(), JsValue> {
+        self.chip.tick().map_err(to_js_error)
+    }
+
+    // Runs the instructions of a frame then updates the timers and the
+    // persistence, returns true if the display has been drawn to. The frame
+    // ends early before an instruction with a breakpoint, which is passed by
+    // stepping over it.
+    pub fn run_frame(&mut self, ipf: u32) -> Result<bool, JsValue> {
+        let mut changed = false;
+
+        self.breakpoint_hit = false;
+
+        for _ in 0..ipf {
+            if self.breakpoints.contains(&self.chip.get_pc()) {
+                self.breakpoint_hit = true;
+                return Ok(changed);
+            }
+
+            self.chip.tick().map_err(to_js_error)?;
+            changed |= self.chip.display.has_changed();
+        }
+
+        self.chip.update_timers();
+        self.phosphor.update(&self.chip.display);
+
+        Ok(changed)
     }
 
     pub fn update_timers(&mut self) {
@@ -47,15 +275,228 @@ impl WasmChip {
         self.chip.display.get_buffer().as_ptr()
     }
 
-    pub fn set_key(&mut self, key: u8) {
+    pub fn set_persistence_decay(&mut self, decay: u8) {
+        self.phosphor.set_decay(decay);
+    }
+
+    pub fn update_persistence(&mut self) {
+        self.phosphor.update(&self.chip.display);
+    }
+
+    pub fn ptr_persistence_buffer(&self) -> *const u8 {
+        self.phosphor.get_buffer().as_ptr()
+    }
+
+    pub fn step(&mut self) -> Result<(), JsValue> {
+        self.chip.tick().map_err(to_js_error)?;
+        self.phosphor.update(&self.chip.display);
+        Ok(())
+    }
+
+    pub fn has_hit_breakpoint(&self) -> bool {
+        self.breakpoint_hit
+    }
+
+    // Returns true if the breakpoint has been added
+    pub fn toggle_breakpoint(&mut self, address: u16) -> bool {
+        match self.breakpoints.remove(&address) {
+            true => false,
+            false => self.breakpoints.insert(address),
+        }
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    pub fn get_breakpoints(&self) -> Vec<u16> {
+        self.breakpoints.iter().copied().collect()
+    }
+
+    // Lines like "0x200  00E0  CLS", two bytes apart
+    pub fn disassemble(&self, start: u16, count: usize) -> Vec<String> {
+        disassembler::disassemble_range(self.chip.get_memory(), start, count)
+            .into_iter()
+            .map(|line| format!("0x{:03X}  {:04X}  {}", line.address, line.opcode, line.text))
+            .collect()
+    }
+
+    // Lines like "0x200  00 E0 A2 2A 60 0C 61 08"
+    pub fn hexdump(&self, start: u16, rows: usize) -> Vec<String> {
+        let memory = self.chip.get_memory();
+        let bytes = memory.get(start as usize..).unwrap_or_default();
+
+        bytes
+            .chunks(HEXDUMP_WIDTH)
+            .take(rows)
+            .enumerate()
+            .map(|(row, chunk)| {
+                let address = start as usize + row * HEXDUMP_WIDTH;
+                let hex = chunk
+                    .iter()
+                    .map(|byte| format!("{:02X}", byte))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                format!("0x{:03X}  {}", address, hex)
+            })
+            .collect()
+    }
+
+    pub fn themes() -> Vec<String> {
+        Theme::ALL
+            .iter()
+            .map(|theme| theme.name().to_string())
+            .collect()
+    }
+
+    // Goes back to white on black without a theme
+    pub fn set_theme(&mut self, name: Option<String>) -> Result<(), JsValue> {
+        self.palette = match name {
+            Some(name) => Theme::from_name(&name)
+                .ok_or_else(|| JsValue::from_str(&format!("Unknown theme: {}", name)))?
+                .palette(),
+            None => Palette::default(),
+        };
+
+        Ok(())
+    }
+
+    // Colors in the #RRGGBB form
+    pub fn set_colors(&mut self, fg: &str, bg: &str) -> Result<(), JsValue> {
+        self.palette.set_fg(parse_color(fg)?);
+        self.palette.set_bg(parse_color(bg)?);
+        Ok(())
+    }
+
+    pub fn set_scale(&mut self, scale: usize) {
+        self.scale = scale.max(1);
+    }
+
+    pub fn set_persistence(&mut self, enabled: bool) {
+        self.persistence = enabled;
+    }
+
+    pub fn has_persistence(&self) -> bool {
+        self.persistence
+    }
+
+    pub fn rgba_width(&self) -> usize {
+        WIDTH * self.scale
+    }
+
+    pub fn rgba_height(&self) -> usize {
+        HEIGHT * self.scale
+    }
+
+    // The buffer can move after each render
+    pub fn render_rgba(&mut self) {
+        self.rgba = match self.persistence {
+            true => self.phosphor.render_rgba(&self.palette, self.scale),
+            false => self.chip.display.render_rgba(&self.palette, self.scale),
+        };
+    }
+
+    pub fn ptr_rgba_buffer(&self) -> *const u8 {
+        self.rgba.as_ptr()
+    }
+
+    pub fn is_sound_playing(&self) -> bool {
+        self.chip.get_sound_timer() > 0
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.beeper.set_sample_rate(sample_rate);
+    }
+
+    pub fn set_pitch(&mut self, pitch: f32) {
+        self.beeper.set_pitch(pitch);
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.beeper.set_volume(volume);
+    }
+
+    // PCM samples for the current state of the sound timer
+    pub fn generate_samples(&mut self, count: usize) -> Vec<f32> {
+        let mut samples = vec![0.0; count];
+
+        self.beeper.generate(&mut samples, self.is_sound_playing());
+
+        samples
+    }
+
+    pub fn set_key(&mut self, key: u8) -> Result<(), JsValue> {
+        check_key(key)?;
         self.chip.keyboard.set_key(key);
+        Ok(())
     }
 
-    pub fn unset_key(&mut self, key: u8) {
+    pub fn unset_key(&mut self, key: u8) -> Result<(), JsValue> {
+        check_key(key)?;
         self.chip.keyboard.unset_key(key);
+        Ok(())
     }
 
     pub fn get_key(&self) -> Option<u8> {
         self.chip.keyboard.get_pressed()
     }
 }
+
+#[wasm_bindgen]
+pub struct WasmKeymap {
+    keymap: Keymap,
+}
+
+#[wasm_bindgen]
+impl WasmKeymap {
+    #[wasm_bindgen(constructor)]
+    pub fn new(preset: &str) -> Self {
+        let preset = Preset::from_name(preset).unwrap_or_default();
+
+        Self {
+            keymap: Keymap::from_preset(preset),
+        }
+    }
+
+    pub fn presets() -> Vec<String> {
+        Preset::ALL
+            .iter()
+            .map(|preset| preset.name().to_string())
+            .collect()
+    }
+
+    pub fn set_key(&mut self, key: u8, name: &str) -> Result<(), JsValue> {
+        check_key(key)?;
+        self.keymap.set_key(key, name);
+        Ok(())
+    }
+
+    pub fn get_key(&self, name: &str) -> Option<u8> {
+        self.keymap.get_key(name)
+    }
+
+    pub fn get_name(&self, key: u8) -> Result<String, JsValue> {
+        check_key(key)?;
+        Ok(self.keymap.get_name(key).to_string())
+    }
+}
+
+fn to_js_error(error: Error) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+fn parse_color(color: &str) -> Result<Rgb, JsValue> {
+    Rgb::from_hex(color).ok_or_else(|| JsValue::from_str(&format!("Invalid color: {}", color)))
+}
+
+fn get_platform(id: &str) -> Result<Platform, JsValue> {
+    Platform::from_id(id).ok_or_else(|| JsValue::from_str(&format!("Unknown platform: {}", id)))
+}
+
+fn check_key(key: u8) -> Result<(), JsValue> {
+    match key < KEY_COUNT {
+        true => Ok(()),
+        false => Err(JsValue::from_str(&format!("Invalid key: {}", key))),
+    }
+}
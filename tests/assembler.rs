@@ -0,0 +1,120 @@
+use yace::assembler::assemble;
+
+#[test]
+fn calc_evaluates_from_right_to_left() {
+    let source = "
+        :calc right { 2 * 3 + 4 }
+        :calc grouped { ( 2 * 3 ) + 4 }
+        : main
+          v0 := right
+          v1 := grouped
+    ";
+
+    assert_eq!(
+        assemble(source).unwrap(),
+        [0x12, 0x02, 0x60, 0x0E, 0x61, 0x0A]
+    );
+}
+
+#[test]
+fn if_begin_else_jumps_over_the_branches() {
+    let source = "
+        : main
+          if v0 == 1 begin
+            v1 := 2
+          else
+            v1 := 3
+          end
+    ";
+
+    assert_eq!(
+        assemble(source).unwrap(),
+        [0x12, 0x02, 0x30, 0x01, 0x12, 0x0A, 0x61, 0x02, 0x12, 0x0C, 0x61, 0x03]
+    );
+}
+
+#[test]
+fn while_breaks_out_of_the_loop() {
+    let source = "
+        : main
+          loop
+            v0 += 1
+            while v0 != 5
+          again
+    ";
+
+    assert_eq!(
+        assemble(source).unwrap(),
+        [0x12, 0x02, 0x70, 0x01, 0x40, 0x05, 0x12, 0x0A, 0x12, 0x02]
+    );
+}
+
+#[test]
+fn labels_can_be_used_before_their_definition() {
+    let source = "
+        : main
+          i := data
+          draw
+          jump main
+        : draw
+          sprite v0 v1 1
+          return
+        : data
+          0xFF
+    ";
+
+    assert_eq!(
+        assemble(source).unwrap(),
+        [0x12, 0x02, 0xA2, 0x0C, 0x22, 0x08, 0x12, 0x02, 0xD0, 0x11, 0x00, 0xEE, 0xFF]
+    );
+}
+
+#[test]
+fn undefined_labels_are_reported_with_their_line() {
+    let error = assemble(": main\n  jump nowhere\n").unwrap_err();
+
+    assert_eq!(error.to_string(), "line 2: Undefined label: nowhere");
+}
+
+#[test]
+fn recursive_macros_are_reported() {
+    let error = assemble(":macro forever { forever }\n: main\n  forever\n").unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "line 1: Macro expansion too deep: forever"
+    );
+}
+
+#[test]
+fn nested_macros_are_expanded() {
+    let source = "
+        :macro set reg value { reg := value }
+        :macro both value { set v0 value set v1 value }
+        : main
+          both 7
+    ";
+
+    assert_eq!(
+        assemble(source).unwrap(),
+        [0x12, 0x02, 0x60, 0x07, 0x61, 0x07]
+    );
+}
+
+#[test]
+fn calc_overflows_are_reported() {
+    for operator in ["/", "%"] {
+        let source = format!(
+            ":calc x {{ ( - 9223372036854775807 - 1 ) {} -1 }}\n: main\n",
+            operator
+        );
+
+        assert_eq!(
+            assemble(&source).unwrap_err().to_string(),
+            "line 1: Division overflow"
+        );
+    }
+
+    let error = assemble(":calc x { 1 / 0 }\n: main\n").unwrap_err();
+    assert_eq!(error.to_string(), "line 1: Division by zero");
+}
@@ -0,0 +1,88 @@
+use yace::audio::{Beeper, DEFAULT_VOLUME};
+
+fn generate(beeper: &mut Beeper, len: usize, active: bool) -> Vec<f32> {
+    let mut buffer = vec![1.0; len];
+    beeper.generate(&mut buffer, active);
+    buffer
+}
+
+#[test]
+fn inactive_beeper_is_silent() {
+    let mut beeper = Beeper::default();
+
+    assert!(generate(&mut beeper, 128, false).iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn active_beeper_plays_a_square_wave() {
+    let mut beeper = Beeper::new(8000);
+
+    beeper.set_pitch(1000.0);
+
+    // 8 samples per period, half of them high
+    let samples = generate(&mut beeper, 16, true);
+    let high = [DEFAULT_VOLUME; 4];
+    let low = [-DEFAULT_VOLUME; 4];
+
+    assert_eq!(samples, [high, low, high, low].concat());
+}
+
+#[test]
+fn samples_are_bounded_by_the_volume() {
+    let mut beeper = Beeper::default();
+
+    beeper.set_volume(0.5);
+    assert!(generate(&mut beeper, 1024, true)
+        .iter()
+        .all(|&s| s == 0.5 || s == -0.5));
+
+    beeper.set_volume(2.0);
+    assert!(generate(&mut beeper, 1024, true)
+        .iter()
+        .all(|&s| s.abs() == 1.0));
+}
+
+#[test]
+fn wave_continues_between_buffers() {
+    let mut beeper = Beeper::new(8000);
+    let mut split = Beeper::new(8000);
+
+    beeper.set_pitch(1000.0);
+    split.set_pitch(1000.0);
+
+    let whole = generate(&mut beeper, 12, true);
+    let parts = [generate(&mut split, 5, true), generate(&mut split, 7, true)].concat();
+
+    assert_eq!(whole, parts);
+}
+
+#[test]
+fn silence_restarts_the_wave() {
+    let mut beeper = Beeper::new(8000);
+
+    beeper.set_pitch(1000.0);
+    generate(&mut beeper, 5, true);
+    generate(&mut beeper, 1, false);
+
+    assert_eq!(generate(&mut beeper, 1, true), [DEFAULT_VOLUME]);
+}
+
+#[test]
+fn pitch_is_limited_to_the_nyquist_frequency() {
+    let mut beeper = Beeper::new(8000);
+
+    beeper.set_pitch(100_000.0);
+
+    // 2 samples per period at 4000 Hz
+    let samples = generate(&mut beeper, 4, true);
+
+    assert_eq!(
+        samples,
+        [
+            DEFAULT_VOLUME,
+            -DEFAULT_VOLUME,
+            DEFAULT_VOLUME,
+            -DEFAULT_VOLUME
+        ]
+    );
+}
@@ -0,0 +1,128 @@
+use yace::{chip::Chip, rom::DEFAULT_ADDRESS};
+
+fn run(chip: &mut Chip, instructions: usize) {
+    for _ in 0..instructions {
+        chip.tick().unwrap();
+    }
+}
+
+#[test]
+fn key_skips_use_the_low_nibble() {
+    // LD V0, 0xFF then SKP V0 and SKNP V0
+    let program = [0x60, 0xFF, 0xE0, 0x9E, 0x60, 0xFF, 0xE0, 0xA1];
+    let mut chip = Chip::new(&program).unwrap();
+
+    chip.keyboard.set_key(0xF);
+    run(&mut chip, 3);
+    assert_eq!(chip.get_pc(), DEFAULT_ADDRESS + 8);
+
+    chip.reset();
+    run(&mut chip, 4);
+    assert_eq!(chip.get_pc(), DEFAULT_ADDRESS + 10);
+}
+
+fn run_until_error(program: &[u8], limit: usize) -> String {
+    let mut chip = Chip::new(program).unwrap();
+
+    (0..limit)
+        .find_map(|_| chip.tick().err())
+        .map(|error| error.to_string())
+        .unwrap_or_default()
+}
+
+#[test]
+fn stack_overflow_is_reported() {
+    // CALL 0x200, which calls itself
+    assert_eq!(
+        run_until_error(&[0x22, 0x00], 17),
+        "Stack overflow at 0x200"
+    );
+}
+
+#[test]
+fn stack_underflow_is_reported() {
+    // RET without a CALL
+    assert_eq!(
+        run_until_error(&[0x00, 0xEE], 1),
+        "Stack underflow at 0x200"
+    );
+}
+
+#[test]
+fn out_of_range_memory_accesses_are_reported() {
+    // LD I, 0xFFF then LD V1, [I] reads past the end of the memory
+    assert_eq!(
+        run_until_error(&[0xAF, 0xFF, 0xF1, 0x65], 2),
+        "Memory access out of range: 0xFFF at 0x202"
+    );
+
+    // JP 0xFFF fetches an instruction across the end of the memory
+    assert_eq!(
+        run_until_error(&[0x1F, 0xFF], 2),
+        "Memory access out of range: 0xFFF at 0xFFF"
+    );
+}
+
+#[test]
+fn unsupported_instructions_are_reported() {
+    assert_eq!(
+        run_until_error(&[0x50, 0x01], 1),
+        "Unsupported instruction: 5001 at 0x200"
+    );
+}
+
+// LD V0, 5 then LD I, 0x300, LD [I], V0 and DRW V0, V0, 5
+const WRITER: [u8; 8] = [0x60, 0x05, 0xA3, 0x00, 0xF0, 0x55, 0xD0, 0x05];
+
+fn run_writer() -> Chip {
+    let mut chip = Chip::new(&WRITER).unwrap();
+
+    chip.quirks.memory = false;
+    run(&mut chip, 4);
+    assert_eq!(chip.get_memory()[0x300], 5);
+    assert!(chip.display.get_rows().iter().any(|&row| row != 0));
+
+    chip
+}
+
+#[test]
+fn reset_restarts_the_loaded_program() {
+    let mut chip = run_writer();
+
+    chip.reset();
+
+    assert_eq!(chip.get_pc(), DEFAULT_ADDRESS);
+    assert_eq!(chip.get_i(), 0);
+    assert_eq!(chip.get_registers(), [0; 16]);
+    assert!(chip.get_stack().is_empty());
+    assert!(chip.display.get_rows().iter().all(|&row| row == 0));
+    assert_eq!(chip.get_program(), WRITER);
+    assert_eq!(chip.get_memory()[0x200..0x208], WRITER);
+    // the rest of the memory is kept
+    assert_eq!(chip.get_memory()[0x300], 5);
+
+    run(&mut chip, 4);
+    assert_eq!(chip.get_pc(), DEFAULT_ADDRESS + 8);
+}
+
+#[test]
+fn power_cycle_clears_the_memory() {
+    let mut chip = run_writer();
+
+    chip.power_cycle();
+
+    assert_eq!(chip.get_memory()[0x200..0x208], WRITER);
+    assert_eq!(chip.get_memory()[0x300], 0);
+}
+
+#[test]
+fn unload_clears_the_program() {
+    let mut chip = run_writer();
+
+    chip.unload();
+
+    assert!(chip.get_program().is_empty());
+    assert_eq!(chip.get_info(), None);
+    assert!(chip.get_memory()[0x200..].iter().all(|&byte| byte == 0));
+    assert_eq!(chip.get_pc(), DEFAULT_ADDRESS);
+}
@@ -0,0 +1,56 @@
+use yace::{
+    chip::Chip,
+    database::{lookup, lookup_program},
+    platform::Platform,
+    quirks::Quirks,
+    rom::DEFAULT_ADDRESS,
+};
+
+const PONG: &[u8] = include_bytes!("../web/public/roms/pong");
+const SPACE_INVADERS: &[u8] = include_bytes!("../web/public/roms/space-invaders");
+const PONG_HASH: &str = "607c4f7f4e4dce9f99d96b3182bfe7e88bb090ee";
+
+#[test]
+fn known_hashes_have_metadata() {
+    let info = lookup(PONG_HASH).unwrap();
+
+    assert_eq!(info.title, "Pong");
+    assert_eq!(info.authors, ["Paul Vervalin"]);
+    assert_eq!(info.platform, Platform::OriginalChip8);
+    assert_eq!(info.quirks, Platform::OriginalChip8.quirks());
+    assert_eq!(info.tickrate, Platform::OriginalChip8.default_tickrate());
+    assert_eq!(info.address, DEFAULT_ADDRESS);
+    assert!(info.keys.contains(&("up".to_string(), 1)));
+    assert!(info.keys.contains(&("player2Down".to_string(), 13)));
+}
+
+#[test]
+fn programs_are_looked_up_by_hash() {
+    assert_eq!(lookup_program(PONG), lookup(PONG_HASH));
+    assert_eq!(lookup(&PONG_HASH.to_uppercase()), lookup(PONG_HASH));
+    assert_eq!(lookup_program(&PONG[1..]), None);
+}
+
+#[test]
+fn quirky_platforms_override_the_defaults() {
+    let info = lookup("5c28a5f85289c9d859f95fd5eadbdcb1c30bb08b").unwrap();
+
+    assert_eq!(info.title, "Space Invaders");
+    assert!(info.quirks.shifting);
+}
+
+#[test]
+fn unknown_programs_get_the_default_quirks() {
+    let mut chip = Chip::new(SPACE_INVADERS).unwrap();
+
+    assert_eq!(
+        Some(chip.quirks),
+        lookup_program(SPACE_INVADERS).map(|info| info.quirks)
+    );
+    assert_ne!(chip.quirks, Quirks::default());
+
+    chip.load(&[0x12, 0x00]).unwrap();
+
+    assert_eq!(chip.quirks, Quirks::default());
+    assert_eq!(chip.get_info(), None);
+}
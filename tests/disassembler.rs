@@ -0,0 +1,72 @@
+use yace::disassembler::{disassemble, disassemble_range, Line};
+
+#[test]
+fn every_opcode_group_is_disassembled() {
+    let cases = [
+        (0x00E0, "CLS"),
+        (0x00EE, "RET"),
+        (0x1A2B, "JP 0xA2B"),
+        (0x2208, "CALL 0x208"),
+        (0x3C0F, "SE VC, 0x0F"),
+        (0x41FF, "SNE V1, 0xFF"),
+        (0x5AB0, "SE VA, VB"),
+        (0x6E12, "LD VE, 0x12"),
+        (0x7001, "ADD V0, 0x01"),
+        (0x8120, "LD V1, V2"),
+        (0x8121, "OR V1, V2"),
+        (0x8122, "AND V1, V2"),
+        (0x8123, "XOR V1, V2"),
+        (0x8124, "ADD V1, V2"),
+        (0x8125, "SUB V1, V2"),
+        (0x8126, "SHR V1, V2"),
+        (0x8127, "SUBN V1, V2"),
+        (0x812E, "SHL V1, V2"),
+        (0x9DE0, "SNE VD, VE"),
+        (0xA123, "LD I, 0x123"),
+        (0xB300, "JP V0, 0x300"),
+        (0xC57F, "RND V5, 0x7F"),
+        (0xD01F, "DRW V0, V1, 15"),
+        (0xE39E, "SKP V3"),
+        (0xE4A1, "SKNP V4"),
+        (0xF507, "LD V5, DT"),
+        (0xF60A, "LD V6, K"),
+        (0xF715, "LD DT, V7"),
+        (0xF818, "LD ST, V8"),
+        (0xF91E, "ADD I, V9"),
+        (0xFA29, "LD F, VA"),
+        (0xFB33, "LD B, VB"),
+        (0xFF55, "LD [I], VF"),
+        (0xF065, "LD V0, [I]"),
+    ];
+
+    for (opcode, text) in cases {
+        assert_eq!(disassemble(opcode), text, "0x{:04X}", opcode);
+    }
+}
+
+#[test]
+fn unknown_opcodes_are_data() {
+    for opcode in [0x0000, 0x0123, 0x5121, 0x8128, 0x9121, 0xE100, 0xF0FF] {
+        assert_eq!(disassemble(opcode), format!("DW 0x{:04X}", opcode));
+    }
+}
+
+#[test]
+fn ranges_stop_at_the_end_of_memory() {
+    let memory = [0x00, 0xE0, 0x12, 0x00, 0xAB];
+    let line = |address, opcode, text: &str| Line {
+        address,
+        opcode,
+        text: text.to_string(),
+    };
+
+    assert_eq!(
+        disassemble_range(&memory, 0, 10),
+        [line(0, 0x00E0, "CLS"), line(2, 0x1200, "JP 0x200")]
+    );
+    assert_eq!(
+        disassemble_range(&memory, 1, 1),
+        [line(1, 0xE012, "DW 0xE012")]
+    );
+    assert!(disassemble_range(&memory, 4, 1).is_empty());
+}
@@ -0,0 +1,86 @@
+use yace::{
+    display::{Display, HEIGHT, WIDTH},
+    palette::{Palette, Rgb},
+};
+
+fn unpack(display: &Display) -> Vec<u8> {
+    display
+        .get_rows()
+        .iter()
+        .flat_map(|row| (0..WIDTH).map(move |x| (row >> (WIDTH - 1 - x) & 1) as u8))
+        .collect()
+}
+
+#[test]
+fn buffer_follows_the_rows() {
+    let mut display = Display::default();
+    let sprite = [0xFF, 0x81, 0xA5, 0x18];
+
+    for (x, y) in [(0, 0), (60, 30), (3, 1), (63, 31), (100, 45)] {
+        for clip in [false, true] {
+            display.load_sprite(x, y, &sprite, clip);
+            assert_eq!(display.get_buffer(), unpack(&display).as_slice());
+        }
+    }
+}
+
+#[test]
+fn sprites_wrap_or_clip() {
+    let mut display = Display::default();
+
+    display.load_sprite(62, HEIGHT - 1, &[0xC3, 0xFF], false);
+
+    assert_eq!(display.get_rows()[HEIGHT - 1], 0x0C00_0000_0000_0003);
+    assert_eq!(display.get_rows()[0], 0xFC00_0000_0000_0003);
+
+    display.clear();
+    display.load_sprite(62, HEIGHT - 1, &[0xC3, 0xFF], true);
+
+    assert_eq!(display.get_rows()[HEIGHT - 1], 0x3);
+    assert_eq!(display.get_rows()[0], 0);
+}
+
+#[test]
+fn collisions_are_reported() {
+    let mut display = Display::default();
+
+    assert!(!display.load_sprite(10, 5, &[0xF0], false));
+    assert!(display.load_sprite(13, 5, &[0x80], false));
+    assert!(!display.load_sprite(14, 5, &[0x80], false));
+    assert_eq!(display.get_buffer()[5 * WIDTH + 13], 0);
+    assert_eq!(display.get_buffer()[5 * WIDTH + 14], 1);
+}
+
+#[test]
+fn rgba_output_is_scaled() {
+    let display = Display::default();
+    let palette = Palette::default();
+
+    for scale in [1, 2, 5] {
+        let rgba = display.render_rgba(&palette, scale);
+        assert_eq!(rgba.len(), WIDTH * HEIGHT * 4 * scale * scale);
+    }
+
+    // a scale of zero is treated as one
+    assert_eq!(display.render_rgba(&palette, 0).len(), WIDTH * HEIGHT * 4);
+}
+
+#[test]
+fn rgba_output_uses_the_palette() {
+    let palette = Palette::new(Rgb::new(0xFF, 0xB0, 0x00), Rgb::new(0x1A, 0x0F, 0x00));
+    let mut display = Display::default();
+
+    display.load_sprite(0, 0, &[0x80], false);
+
+    let rgba = display.render_rgba(&palette, 2);
+    let pixel = |x: usize, y: usize| &rgba[(y * WIDTH * 2 + x) * 4..][..4];
+
+    // the lit pixel covers a 2x2 square
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        assert_eq!(pixel(x, y), [0xFF, 0xB0, 0x00, 0xFF]);
+    }
+
+    for (x, y) in [(2, 0), (0, 2), (WIDTH * 2 - 1, HEIGHT * 2 - 1)] {
+        assert_eq!(pixel(x, y), [0x1A, 0x0F, 0x00, 0xFF]);
+    }
+}
@@ -0,0 +1,67 @@
+use yace::keymap::{self, Keymap, Preset};
+
+#[test]
+fn presets_are_found_by_name() {
+    for preset in Preset::ALL {
+        assert_eq!(Preset::from_name(preset.name()), Some(preset));
+        assert_eq!(
+            Preset::from_name(&preset.name().to_uppercase()),
+            Some(preset)
+        );
+    }
+
+    assert_eq!(Preset::from_name("colemak"), None);
+    assert_eq!(Preset::from_name(""), None);
+}
+
+#[test]
+fn presets_bind_the_keypad_layout() {
+    let qwerty = Keymap::from_preset(Preset::Qwerty);
+    let dvorak = Keymap::from_preset(Preset::Dvorak);
+    let numpad = Keymap::from_preset(Preset::Numpad);
+
+    assert_eq!(qwerty, Keymap::default());
+    assert_eq!(qwerty.get_key("x"), Some(0x0));
+    assert_eq!(qwerty.get_key("4"), Some(0xC));
+    assert_eq!(qwerty.get_key("V"), Some(0xF));
+    assert_eq!(qwerty.get_key("p"), None);
+
+    assert_eq!(dvorak.get_key("'"), Some(0x4));
+    assert_eq!(dvorak.get_name(0xF), "k");
+
+    assert_eq!(numpad.get_key("Enter"), Some(0xE));
+    assert_eq!(numpad.get_key("Return"), Some(0xE));
+    assert_eq!(numpad.get_name(0xA), "/");
+}
+
+#[test]
+fn every_preset_binds_sixteen_different_keys() {
+    for preset in Preset::ALL {
+        let keymap = Keymap::from_preset(preset);
+
+        for key in 0..16 {
+            assert_eq!(keymap.get_key(keymap.get_name(key)), Some(key));
+        }
+    }
+}
+
+#[test]
+fn key_names_are_normalized() {
+    assert_eq!(keymap::normalize("ArrowUp"), "up");
+    assert_eq!(keymap::normalize("Up"), "up");
+    assert_eq!(keymap::normalize(" "), "space");
+    assert_eq!(keymap::normalize("Escape"), "esc");
+    assert_eq!(keymap::normalize("Return"), "enter");
+    assert_eq!(keymap::normalize("Q"), "q");
+}
+
+#[test]
+fn keys_can_be_rebound() {
+    let mut keymap = Keymap::default();
+
+    keymap.set_key(0x5, "ArrowLeft");
+
+    assert_eq!(keymap.get_key("left"), Some(0x5));
+    assert_eq!(keymap.get_key("w"), None);
+    assert_eq!(keymap.get_name(0x5), "left");
+}
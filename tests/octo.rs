@@ -0,0 +1,50 @@
+use std::fs::File;
+use yace::{
+    octo::Cartridge,
+    palette::{Palette, Rgb},
+    quirks::Quirks,
+};
+
+fn decode_fixture() -> Cartridge {
+    Cartridge::decode(File::open("tests/fixtures/counter.gif").unwrap()).unwrap()
+}
+
+#[test]
+fn cartridges_hold_the_source_and_options() {
+    let cartridge = decode_fixture();
+    let options = &cartridge.options;
+
+    assert!(cartridge.program.starts_with(": main"));
+    assert_eq!(options.tickrate, Some(20));
+    assert_eq!(options.fill_color.as_deref(), Some("#FF6600"));
+    assert_eq!(options.shift_quirks, Some(true));
+    assert_eq!(options.clip_quirks, Some(false));
+    assert_eq!(options.jump_quirks, None);
+}
+
+#[test]
+fn cartridge_options_override_the_defaults() {
+    let options = decode_fixture().options;
+    let base = Quirks::default();
+    let quirks = options.get_quirks(base);
+    let palette = options.get_palette(Palette::default());
+
+    assert!(quirks.shifting);
+    assert!(!quirks.clipping);
+    assert_eq!(quirks.jumping, base.jumping);
+    assert_eq!(palette.fg(), Rgb::new(0xFF, 0x66, 0x00));
+}
+
+#[test]
+fn cartridge_programs_are_assembled() {
+    let program = decode_fixture().assemble().unwrap();
+
+    assert_eq!(program, [0x12, 0x02, 0x60, 0x01, 0x70, 0x01, 0x12, 0x04]);
+}
+
+#[test]
+fn other_images_are_rejected() {
+    let error = Cartridge::decode(&b"GIF89a"[..]).unwrap_err();
+
+    assert!(error.to_string().starts_with("Invalid Octo cartridge"));
+}
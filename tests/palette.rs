@@ -0,0 +1,65 @@
+use yace::palette::{Palette, Rgb, Theme};
+
+#[test]
+fn hex_colors_are_parsed() {
+    assert_eq!(Rgb::from_hex("#FF6600"), Some(Rgb::new(255, 102, 0)));
+    assert_eq!(Rgb::from_hex("#0a140a"), Some(Rgb::new(10, 20, 10)));
+    assert_eq!(Rgb::from_hex("#000000"), Some(Rgb::new(0, 0, 0)));
+}
+
+#[test]
+fn invalid_hex_colors_are_rejected() {
+    for value in [
+        "", "#", "FF6600", "#FF660", "#FF66000", "#F60", "#GG6600", "#+F6600", "#ÿÿÿ",
+    ] {
+        assert_eq!(Rgb::from_hex(value), None, "{}", value);
+    }
+}
+
+#[test]
+fn hex_colors_round_trip() {
+    let color = Rgb::new(0x12, 0xAB, 0xEF);
+
+    assert_eq!(color.to_hex(), "#12ABEF");
+    assert_eq!(Rgb::from_hex(&color.to_hex()), Some(color));
+}
+
+#[test]
+fn colors_are_blended() {
+    let black = Rgb::new(0, 0, 0);
+    let white = Rgb::new(255, 255, 255);
+
+    assert_eq!(black.blend(white, 0), black);
+    assert_eq!(black.blend(white, 255), white);
+    assert_eq!(black.blend(white, 51), Rgb::new(51, 51, 51));
+}
+
+#[test]
+fn pixels_index_the_palette() {
+    let palette = Palette::new(Rgb::new(1, 2, 3), Rgb::new(4, 5, 6));
+
+    assert_eq!(palette.get_color(0), palette.bg());
+    assert_eq!(palette.get_color(1), palette.fg());
+    assert_eq!(palette.get_color(0b101), palette.fg());
+}
+
+#[test]
+fn themes_are_found_by_name() {
+    for theme in Theme::ALL {
+        assert_eq!(Theme::from_name(theme.name()), Some(theme));
+    }
+
+    assert_eq!(
+        Theme::from_name("Green_Phosphor"),
+        Some(Theme::GreenPhosphor)
+    );
+    assert_eq!(Theme::from_name("sepia"), None);
+    assert_eq!(
+        Theme::Amber.palette().fg(),
+        Rgb::from_hex("#FFB000").unwrap()
+    );
+    assert_eq!(
+        Theme::Amber.palette().bg(),
+        Rgb::from_hex("#1A0F00").unwrap()
+    );
+}
@@ -0,0 +1,89 @@
+use yace::{
+    display::Display,
+    palette::{Palette, Rgb},
+    phosphor::Phosphor,
+};
+
+// A single lit pixel in the top left corner
+fn lit_display() -> Display {
+    let mut display = Display::default();
+    display.load_sprite(0, 0, &[0x80], false);
+    display
+}
+
+#[test]
+fn lit_pixels_fade_out_by_the_decay() {
+    let mut display = lit_display();
+    let mut phosphor = Phosphor::new(100);
+
+    phosphor.update(&display);
+    assert_eq!(phosphor.get_buffer()[0], 255);
+    assert_eq!(phosphor.get_buffer()[1], 0);
+
+    display.clear();
+
+    let levels = (0..4)
+        .map(|_| {
+            phosphor.update(&display);
+            phosphor.get_buffer()[0]
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(levels, [155, 55, 0, 0]);
+}
+
+#[test]
+fn lit_pixels_go_back_to_full_intensity() {
+    let mut display = lit_display();
+    let mut phosphor = Phosphor::new(100);
+
+    phosphor.update(&display);
+    display.clear();
+    phosphor.update(&display);
+    display.load_sprite(0, 0, &[0x80], false);
+    phosphor.update(&display);
+
+    assert_eq!(phosphor.get_buffer()[0], 255);
+}
+
+#[test]
+fn decay_is_at_least_one() {
+    let mut display = lit_display();
+    let mut phosphor = Phosphor::new(0);
+
+    phosphor.update(&display);
+    display.clear();
+    phosphor.update(&display);
+    assert_eq!(phosphor.get_buffer()[0], 254);
+
+    phosphor.set_decay(0);
+    phosphor.update(&display);
+    assert_eq!(phosphor.get_buffer()[0], 253);
+}
+
+#[test]
+fn clear_turns_every_pixel_off() {
+    let mut phosphor = Phosphor::default();
+
+    phosphor.update(&lit_display());
+    phosphor.clear();
+
+    assert!(phosphor.get_buffer().iter().all(|&level| level == 0));
+}
+
+#[test]
+fn fading_pixels_blend_the_colors() {
+    let palette = Palette::new(Rgb::new(255, 255, 255), Rgb::new(0, 0, 0));
+    let mut display = lit_display();
+    let mut phosphor = Phosphor::new(128);
+
+    phosphor.update(&display);
+    assert_eq!(
+        phosphor.render_rgba(&palette, 1)[..8],
+        [255, 255, 255, 255, 0, 0, 0, 255]
+    );
+
+    display.clear();
+    phosphor.update(&display);
+    assert_eq!(phosphor.render_rgba(&palette, 1)[..4], [127, 127, 127, 255]);
+}
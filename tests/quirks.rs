@@ -0,0 +1,96 @@
+use yace::{chip::Chip, quirks::Quirks};
+
+fn run(program: &[u8], quirks: Quirks, instructions: usize) -> Chip {
+    let mut chip = Chip::new(program).unwrap();
+
+    chip.quirks = quirks;
+
+    for _ in 0..instructions {
+        chip.tick().unwrap();
+    }
+
+    chip
+}
+
+fn with(name: &str, value: bool) -> Quirks {
+    let mut quirks = Quirks::default();
+
+    quirks.set(name, value);
+    quirks
+}
+
+#[test]
+fn vf_reset_clears_the_flag_after_logic_operations() {
+    for opcode in [0x01, 0x02, 0x03] {
+        // LD VF, 5 then LD V1, 3 and OR, AND or XOR V0, V1
+        let program = [0x6F, 0x05, 0x61, 0x03, 0x80, 0x10 | opcode];
+
+        assert_eq!(
+            run(&program, with("vf_reset", true), 3).get_registers()[0xF],
+            0
+        );
+        assert_eq!(
+            run(&program, with("vf_reset", false), 3).get_registers()[0xF],
+            5
+        );
+    }
+}
+
+#[test]
+fn shifting_uses_vx_or_vy_as_source() {
+    // LD V0, 1 then LD V1, 6 and SHR V0, V1
+    let program = [0x60, 0x01, 0x61, 0x06, 0x80, 0x16];
+
+    assert_eq!(
+        run(&program, with("shifting", true), 3).get_registers()[..2],
+        [0, 6]
+    );
+    assert_eq!(
+        run(&program, with("shifting", false), 3).get_registers()[..2],
+        [3, 6]
+    );
+
+    // same with SHL V0, V1
+    let program = [0x60, 0x01, 0x61, 0x06, 0x80, 0x1E];
+
+    assert_eq!(
+        run(&program, with("shifting", true), 3).get_registers()[0],
+        2
+    );
+    assert_eq!(
+        run(&program, with("shifting", false), 3).get_registers()[0],
+        12
+    );
+}
+
+#[test]
+fn jumping_adds_vx_or_v0() {
+    // LD V0, 2 then LD V1, 4 and JP V0, 0x104
+    let program = [0x60, 0x02, 0x61, 0x04, 0xB1, 0x04];
+
+    assert_eq!(run(&program, with("jumping", true), 3).get_pc(), 0x108);
+    assert_eq!(run(&program, with("jumping", false), 3).get_pc(), 0x106);
+}
+
+#[test]
+fn memory_increments_i_after_loads_and_stores() {
+    for opcode in [0x55, 0x65] {
+        // LD I, 0x300 then LD [I], V1 or LD V1, [I]
+        let program = [0xA3, 0x00, 0xF1, opcode];
+
+        assert_eq!(run(&program, with("memory", true), 2).get_i(), 0x302);
+        assert_eq!(run(&program, with("memory", false), 2).get_i(), 0x300);
+    }
+}
+
+#[test]
+fn clipping_cuts_sprites_at_the_edge() {
+    // LD V0, 62 then LD V1, 0, LD I, 0x208 and DRW V0, V1, 1 with a full row
+    let program = [0x60, 0x3E, 0x61, 0x00, 0xA2, 0x08, 0xD0, 0x11, 0xFF];
+
+    let chip = run(&program, with("clipping", true), 4);
+    assert_eq!(chip.display.get_rows()[0], 0x3);
+
+    let chip = run(&program, with("clipping", false), 4);
+    assert_eq!(chip.display.get_rows()[0], 0xFC00_0000_0000_0003);
+}
@@ -0,0 +1,62 @@
+use yace::{
+    display::{HEIGHT, WIDTH},
+    palette::Palette,
+    recorder::GifRecorder,
+};
+
+fn record(frames: &[(u8, usize)], scale: usize) -> Vec<gif::Frame<'static>> {
+    let mut recorder = GifRecorder::new(Vec::new(), Palette::default(), scale).unwrap();
+
+    for &(pixel, count) in frames {
+        for _ in 0..count {
+            recorder.add_frame(&[pixel; WIDTH * HEIGHT]).unwrap();
+        }
+    }
+
+    let bytes = recorder.finish().unwrap();
+    let mut decoder = gif::DecodeOptions::new()
+        .read_info(bytes.as_slice())
+        .unwrap();
+    let mut decoded = Vec::new();
+
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        decoded.push(frame.clone());
+    }
+
+    decoded
+}
+
+#[test]
+fn identical_frames_are_merged_with_60_hz_delays() {
+    let frames = record(&[(0, 6), (1, 3), (0, 1)], 1);
+    let delays = frames.iter().map(|frame| frame.delay).collect::<Vec<_>>();
+
+    // 6 frames last 10 centiseconds, the last frame gets the minimum delay
+    assert_eq!(delays, [10, 5, 2]);
+}
+
+#[test]
+fn frames_shorter_than_the_minimum_delay_are_dropped() {
+    let frames = record(&[(0, 1), (1, 1)], 1);
+
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].delay, 3);
+    assert!(frames[0].buffer.iter().all(|&pixel| pixel == 1));
+}
+
+#[test]
+fn frames_are_scaled() {
+    let frames = record(&[(1, 1)], 3);
+
+    assert_eq!((frames[0].width, frames[0].height), (192, 96));
+    assert_eq!(frames[0].buffer.len(), 192 * 96);
+}
+
+#[test]
+fn scales_beyond_the_gif_size_are_rejected() {
+    let error = GifRecorder::new(Vec::new(), Palette::default(), 2000)
+        .err()
+        .unwrap();
+
+    assert_eq!(error.to_string(), "Recording too large at scale 2000");
+}
@@ -0,0 +1,43 @@
+use std::io::ErrorKind;
+use yace::rom::{get_hash, get_max_size, validate, DEFAULT_ADDRESS, ETI_660_ADDRESS};
+
+#[test]
+fn roms_must_fit_after_their_load_address() {
+    assert_eq!(get_max_size(DEFAULT_ADDRESS), 3584);
+    assert_eq!(get_max_size(ETI_660_ADDRESS), 2560);
+
+    assert!(validate(&[0; 3584], DEFAULT_ADDRESS).is_ok());
+    assert!(validate(&[0; 2560], ETI_660_ADDRESS).is_ok());
+
+    let error = validate(&[0; 3585], DEFAULT_ADDRESS).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        error.to_string(),
+        "ROM too large for platform: 3585 bytes, the maximum is 3584 bytes at 0x200"
+    );
+
+    // fits at 0x200 but not after the ETI-660 interpreter
+    let error = validate(&[0; 3000], ETI_660_ADDRESS).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "ROM too large for platform: 3000 bytes, the maximum is 2560 bytes at 0x600"
+    );
+}
+
+#[test]
+fn load_addresses_must_be_in_the_program_area() {
+    for address in [0x000, 0x1FF, 0x1000, 0xFFFF] {
+        let error = validate(&[0x00, 0xE0], address).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            format!("Invalid load address: 0x{:03X}", address)
+        );
+    }
+}
+
+#[test]
+fn hashes_are_lowercase_sha1() {
+    assert_eq!(get_hash(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+}
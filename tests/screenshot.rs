@@ -0,0 +1,49 @@
+use std::fs;
+use yace::{
+    display::Display,
+    palette::{Palette, Rgb},
+    screenshot::{Format, Screenshot},
+};
+
+// The digits 0 and 1 of the font, drawn at the top left corner
+fn get_display() -> Display {
+    let mut display = Display::default();
+
+    display.load_sprite(0, 0, &[0xF0, 0x90, 0x90, 0x90, 0xF0], false);
+    display.load_sprite(5, 0, &[0x20, 0x60, 0x20, 0x20, 0x70], false);
+    display
+}
+
+fn encode(scale: usize, format: Format) -> String {
+    let display = get_display();
+    let palette = Palette::new(Rgb::new(255, 255, 255), Rgb::new(0, 0, 0));
+    let screenshot = Screenshot::new(display.get_buffer())
+        .with_scale(scale)
+        .with_palette(palette);
+
+    String::from_utf8(screenshot.encode(format)).unwrap()
+}
+
+fn read_fixture(name: &str) -> String {
+    fs::read_to_string(format!("tests/fixtures/{}", name)).unwrap()
+}
+
+#[test]
+fn pbm_matches_golden_file() {
+    assert_eq!(encode(1, Format::Pbm), read_fixture("digits.pbm"));
+    assert_eq!(encode(2, Format::Pbm), read_fixture("digits-x2.pbm"));
+}
+
+#[test]
+fn pgm_matches_golden_file() {
+    assert_eq!(encode(1, Format::Pgm), read_fixture("digits.pgm"));
+}
+
+#[test]
+fn plain_lines_are_at_most_70_characters() {
+    for format in [Format::Pbm, Format::Pgm] {
+        for scale in 1..4 {
+            assert!(encode(scale, format).lines().all(|line| line.len() <= 70));
+        }
+    }
+}
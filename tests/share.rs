@@ -0,0 +1,40 @@
+use yace::{
+    palette::{Palette, Rgb},
+    platform::Platform,
+    share::Share,
+};
+
+const PONG: &[u8] = include_bytes!("../web/public/roms/pong");
+
+#[test]
+fn shares_survive_an_encoding_round_trip() {
+    let share = Share {
+        address: 0x600,
+        quirks: Platform::SuperChip.quirks(),
+        tickrate: Some(1000),
+        palette: Some(Palette::new(Rgb::new(255, 102, 0), Rgb::new(0, 0, 32))),
+        ..Share::new(PONG)
+    };
+    let code = share.encode();
+
+    assert_eq!(Share::decode(&code).unwrap(), share);
+    assert_eq!(
+        Share::decode(&format!("https://example.com/#{}", code)).unwrap(),
+        share
+    );
+}
+
+#[test]
+fn settings_are_optional() {
+    let share = Share::new(PONG);
+
+    assert_eq!(Share::decode(&share.encode()).unwrap(), share);
+}
+
+#[test]
+fn invalid_codes_are_rejected() {
+    for code in ["", "not base64!", "AAAA"] {
+        let error = Share::decode(code).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid share code"));
+    }
+}
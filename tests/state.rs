@@ -0,0 +1,25 @@
+use yace::{chip::Chip, state::State};
+
+#[test]
+fn states_survive_an_encoding_round_trip() {
+    // LD V0, 0x2A then LD I, 0x300, CALL 0x208 and DRW V0, V0, 5
+    let program = [0x60, 0x2A, 0xA3, 0x00, 0x22, 0x08, 0x00, 0x00, 0xD0, 0x05];
+    let mut chip = Chip::new(&program).unwrap();
+
+    for _ in 0..4 {
+        chip.tick().unwrap();
+    }
+
+    let state = chip.save_state();
+
+    assert_eq!(State::decode(&state.encode()).unwrap(), state);
+}
+
+#[test]
+fn truncated_states_are_rejected() {
+    let chip = Chip::new(&[0x12, 0x00]).unwrap();
+    let bytes = chip.save_state().encode();
+
+    assert!(State::decode(&bytes[..bytes.len() - 1]).is_err());
+    assert!(State::decode(b"NOPE").is_err());
+}
@@ -0,0 +1,47 @@
+// Each chunk holds the samples of one frame, a longer queue means that the
+// emulator runs ahead of the audio clock and would keep adding latency
+const MAX_CHUNKS = 8;
+
+// Plays the samples generated by the emulator, silence fills the gaps
+class BeeperProcessor extends AudioWorkletProcessor {
+  constructor() {
+    super();
+    this.chunks = [];
+    this.offset = 0;
+    this.port.onmessage = (event) => this.queue(event.data);
+  }
+
+  // The oldest chunks are dropped when the queue is full
+  queue(chunk) {
+    this.chunks.push(chunk);
+
+    while (this.chunks.length > MAX_CHUNKS) {
+      this.chunks.shift();
+      this.offset = 0;
+    }
+  }
+
+  process(_inputs, outputs) {
+    const output = outputs[0][0];
+
+    for (let i = 0; i < output.length; i++) {
+      const chunk = this.chunks[0];
+
+      if (!chunk) {
+        output.fill(0, i);
+        break;
+      }
+
+      output[i] = chunk[this.offset++];
+
+      if (this.offset >= chunk.length) {
+        this.chunks.shift();
+        this.offset = 0;
+      }
+    }
+
+    return true;
+  }
+}
+
+registerProcessor("beeper", BeeperProcessor);
@@ -0,0 +1,22 @@
+let context: AudioContext | undefined;
+let beeper: AudioWorkletNode | undefined;
+
+// Browsers only allow audio after a user interaction, returns the sample
+// rate that the samples should be generated at
+export const initAudio = async () => {
+  if (!context) {
+    context = new AudioContext();
+    await context.audioWorklet.addModule("/yace/beeper-worklet.js");
+
+    beeper = new AudioWorkletNode(context, "beeper");
+    beeper.connect(context.destination);
+  }
+
+  return context.sampleRate;
+};
+
+export const playSamples = (samples: Float32Array[]) => {
+  for (const chunk of samples) {
+    beeper?.port.postMessage(chunk, [chunk.buffer]);
+  }
+};
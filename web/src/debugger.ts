@@ -0,0 +1,63 @@
+import { call, send } from "./emulator";
+import type { DebugInfo } from "./protocol";
+
+const hex = (value: number, digits: number) =>
+  value.toString(16).toUpperCase().padStart(digits, "0");
+
+const getElement = (id: string) => document.getElementById(id) as HTMLElement;
+
+// The worker only sends the debug info while the panel is open, clicking an
+// instruction toggles a breakpoint on it
+export const initDebugger = () => {
+  const container = getElement("debug-container") as HTMLDetailsElement;
+  const disassembly = getElement("debug-disassembly");
+
+  container.addEventListener("toggle", () =>
+    send({ type: "debug", enabled: container.open })
+  );
+
+  disassembly.addEventListener("click", (event) => {
+    const line = (event.target as HTMLElement).closest("[data-address]");
+
+    if (line instanceof HTMLElement && line.dataset.address) {
+      call("toggle_breakpoint", parseInt(line.dataset.address));
+    }
+  });
+};
+
+export const updateDebugger = (info: DebugInfo) => {
+  const { pc, i } = info;
+  const registers = Array.from(info.registers, (value, index) => {
+    return `V${hex(index, 1)} ${hex(value, 2)}`;
+  });
+  const stack = Array.from(info.stack, (address) => hex(address, 3));
+
+  getElement("debug-registers").innerText = [
+    ...registers,
+    `I  ${hex(i, 3)}`,
+    `PC ${hex(pc, 3)}`,
+    `DT ${hex(info.dt, 2)}`,
+    `ST ${hex(info.st, 2)}`,
+    `SP ${stack.length}`,
+    ...stack.reverse().map((address) => `   ${address}`),
+  ].join("\n");
+
+  const breakpoints = new Set(info.breakpoints);
+
+  getElement("debug-disassembly").replaceChildren(
+    ...info.disassembly.map((text, index) => {
+      const address = info.disassemblyStart + index * 2;
+      const line = document.createElement("div");
+      const marker = address == pc ? ">" : " ";
+      const breakpoint = breakpoints.has(address) ? "*" : " ";
+
+      line.dataset.address = address.toString();
+      line.innerText = `${marker}${breakpoint} ${text}`;
+      line.classList.toggle("debug-current", address == pc);
+
+      return line;
+    })
+  );
+
+  getElement("debug-memory").innerText = info.memory.join("\n");
+};
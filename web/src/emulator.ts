@@ -0,0 +1,51 @@
+import type { ChipMethod, Command, Frame, Message, Method } from "./protocol";
+
+type Pending = {
+  resolve: (value: any) => void;
+  reject: (reason: string) => void;
+};
+
+// The chip lives in a worker so that the page does not slow down the game
+const worker = new Worker(new URL("./worker.ts", import.meta.url), {
+  type: "module",
+});
+
+const pending = new Map<number, Pending>();
+
+let nextId = 0;
+let frameListener: ((frame: Frame) => void) | undefined;
+
+worker.onmessage = (event: MessageEvent<Message>) => {
+  const message = event.data;
+
+  switch (message.type) {
+    case "result":
+      pending.get(message.id)?.resolve(message.value);
+      pending.delete(message.id);
+      break;
+    case "error":
+      pending.get(message.id)?.reject(message.error);
+      pending.delete(message.id);
+      break;
+    case "frame":
+      frameListener?.(message);
+      break;
+  }
+};
+
+export const call = <M extends ChipMethod>(
+  method: M,
+  ...args: Parameters<Method<M>>
+) =>
+  new Promise<ReturnType<Method<M>>>((resolve, reject) => {
+    const id = nextId++;
+
+    pending.set(id, { resolve, reject });
+    send({ type: "call", id, method, args });
+  });
+
+export const send = (command: Command) => worker.postMessage(command);
+
+export const onFrame = (listener: (frame: Frame) => void) => {
+  frameListener = listener;
+};
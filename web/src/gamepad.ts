@@ -0,0 +1,190 @@
+import { call } from "./emulator";
+import { loadGamepadMapping, saveGamepadMapping } from "./storage";
+
+// Inputs are named "button-N" or "axis-N-" and "axis-N+" for each direction
+type Mapping = Record<string, number>;
+
+const AXIS_THRESHOLD = 0.5;
+
+// Inputs of the standard layout for the key hints of the ROM database
+const HINT_INPUTS: Record<string, string[]> = {
+  up: ["button-12", "axis-1-"],
+  down: ["button-13", "axis-1+"],
+  left: ["button-14", "axis-0-"],
+  right: ["button-15", "axis-0+"],
+  a: ["button-0"],
+  b: ["button-1"],
+};
+
+// Directions of the hexadecimal keypad, used by unknown ROMs
+const DEFAULT_KEYS: Record<string, number> = {
+  up: 0x2,
+  down: 0x8,
+  left: 0x4,
+  right: 0x6,
+  a: 0x5,
+};
+
+const INPUT_NAMES: Record<string, string> = {
+  "button-0": "A",
+  "button-1": "B",
+  "button-2": "X",
+  "button-3": "Y",
+  "button-12": "UP",
+  "button-13": "DOWN",
+  "button-14": "LEFT",
+  "button-15": "RIGHT",
+  "axis-0-": "STICK LEFT",
+  "axis-0+": "STICK RIGHT",
+  "axis-1-": "STICK UP",
+  "axis-1+": "STICK DOWN",
+};
+
+let mapping: Mapping = {};
+let defaultMapping: Mapping = {};
+let romHash: string | undefined;
+let activeInputs = new Set<string>();
+let pressedKeys = new Set<number>();
+let listeningKey: number | undefined;
+
+const getInputName = (input: string) =>
+  INPUT_NAMES[input] ?? input.replace("-", " ").toUpperCase();
+
+const getDefaultMapping = async () => {
+  const known = (await call("get_title")) != undefined;
+  const result: Mapping = {};
+
+  for (const [hint, inputs] of Object.entries(HINT_INPUTS)) {
+    const key = known ? await call("get_hint_key", hint) : DEFAULT_KEYS[hint];
+
+    if (key != undefined) {
+      for (const input of inputs) {
+        result[input] = key;
+      }
+    }
+  }
+
+  return result;
+};
+
+const getActiveInputs = () => {
+  const inputs = new Set<string>();
+
+  for (const gamepad of navigator.getGamepads()) {
+    if (!gamepad) continue;
+
+    gamepad.buttons.forEach((button, index) => {
+      if (button.pressed) inputs.add(`button-${index}`);
+    });
+
+    gamepad.axes.forEach((value, index) => {
+      if (value < -AXIS_THRESHOLD) inputs.add(`axis-${index}-`);
+      if (value > AXIS_THRESHOLD) inputs.add(`axis-${index}+`);
+    });
+  }
+
+  return inputs;
+};
+
+const updateMappingView = () => {
+  const container = document.getElementById("gamepad-mapping") as HTMLElement;
+
+  container.replaceChildren(
+    ...Array.from({ length: 16 }, (_, key) => {
+      const button = document.createElement("button");
+      const inputs = Object.keys(mapping)
+        .filter((input) => mapping[input] == key)
+        .map(getInputName);
+      const label =
+        listeningKey == key ? "PRESS A BUTTON" : inputs.join(", ") || "-";
+
+      button.dataset.key = key.toString();
+      button.innerText = `${key.toString(16).toUpperCase()}: ${label}`;
+
+      return button;
+    })
+  );
+};
+
+const assignInput = (input: string) => {
+  if (listeningKey == undefined) return;
+
+  mapping[input] = listeningKey;
+  listeningKey = undefined;
+  updateMappingView();
+
+  if (romHash) {
+    saveGamepadMapping(romHash, mapping);
+  }
+};
+
+// Clicking a key waits for the next gamepad input to assign it
+export const initGamepad = () => {
+  const container = document.getElementById("gamepad-mapping") as HTMLElement;
+  const resetButton = document.getElementById("btn-reset-mapping");
+
+  container.addEventListener("click", (event) => {
+    const button = (event.target as HTMLElement).closest("[data-key]");
+
+    if (button instanceof HTMLElement && button.dataset.key) {
+      listeningKey = parseInt(button.dataset.key);
+      updateMappingView();
+    }
+  });
+
+  resetButton?.addEventListener("click", () => {
+    mapping = { ...defaultMapping };
+    listeningKey = undefined;
+    updateMappingView();
+
+    if (romHash) {
+      saveGamepadMapping(romHash, mapping);
+    }
+  });
+
+  updateMappingView();
+};
+
+// Restores the mapping saved for the ROM or the one from its key hints
+export const loadMapping = async (hash: string) => {
+  romHash = hash;
+  defaultMapping = await getDefaultMapping();
+
+  const saved = await loadGamepadMapping(hash).catch(() => undefined);
+
+  // another ROM may have been loaded in the meantime
+  if (romHash == hash) {
+    mapping = saved ?? { ...defaultMapping };
+    updateMappingView();
+  }
+};
+
+// Should be called once per frame, the keys are only updated on changes
+export const pollGamepad = (
+  onPress: (key: number) => void,
+  onRelease: (key: number) => void
+) => {
+  const inputs = getActiveInputs();
+  const keys = new Set<number>();
+
+  for (const input of inputs) {
+    if (!activeInputs.has(input)) {
+      assignInput(input);
+    }
+
+    if (mapping[input] != undefined) {
+      keys.add(mapping[input]);
+    }
+  }
+
+  for (const key of keys) {
+    if (!pressedKeys.has(key)) onPress(key);
+  }
+
+  for (const key of pressedKeys) {
+    if (!keys.has(key)) onRelease(key);
+  }
+
+  activeInputs = inputs;
+  pressedKeys = keys;
+};
@@ -0,0 +1,88 @@
+import { deleteRom, loadRoms, saveRom } from "./storage";
+import type { LibraryRom } from "./storage";
+
+// Resolves to false when the ROM is rejected
+type PlayListener = (rom: Uint8Array, name: string) => Promise<boolean>;
+
+let entries = new Map<string, LibraryRom>();
+let playListener: PlayListener | undefined;
+
+const playEntry = async (entry: LibraryRom) => {
+  if (await playListener?.(entry.rom, entry.name)) {
+    await addToLibrary(entry.hash, entry.name, entry.rom);
+  }
+};
+
+const deleteEntry = async (entry: LibraryRom) => {
+  if (confirm(`Delete ${entry.name} with its save states and settings?`)) {
+    await deleteRom(entry.hash);
+    await updateLibrary();
+  }
+};
+
+// Each entry has its own play and delete buttons
+export const initLibrary = (listener: PlayListener) => {
+  const list = document.getElementById("library-list") as HTMLElement;
+
+  playListener = listener;
+
+  list.addEventListener("click", async (event) => {
+    const button = (event.target as HTMLElement).closest("[data-hash]");
+
+    if (!(button instanceof HTMLElement) || !button.dataset.hash) return;
+
+    const entry = entries.get(button.dataset.hash);
+
+    if (entry && button.dataset.action == "play") {
+      await playEntry(entry);
+    } else if (entry && button.dataset.action == "delete") {
+      await deleteEntry(entry);
+    }
+  });
+
+  updateLibrary();
+};
+
+// The most recently played ROMs come first
+export const updateLibrary = async () => {
+  const list = document.getElementById("library-list") as HTMLElement;
+  const roms = await loadRoms().catch((): LibraryRom[] => []);
+
+  roms.sort((a, b) => b.played - a.played);
+  entries = new Map(roms.map((entry) => [entry.hash, entry]));
+
+  if (roms.length == 0) {
+    list.innerText = "Opened ROMs are kept here";
+    return;
+  }
+
+  list.replaceChildren(
+    ...roms.map((entry) => {
+      const row = document.createElement("div");
+      const name = document.createElement("span");
+      const buttons = ["play", "delete"].map((action) => {
+        const button = document.createElement("button");
+
+        button.dataset.hash = entry.hash;
+        button.dataset.action = action;
+        button.innerText = action.toUpperCase();
+
+        return button;
+      });
+
+      name.innerText = entry.name;
+      row.append(name, ...buttons);
+
+      return row;
+    })
+  );
+};
+
+export const addToLibrary = async (
+  hash: string,
+  name: string,
+  rom: Uint8Array
+) => {
+  await saveRom({ hash, name, rom, played: Date.now() });
+  await updateLibrary();
+};
@@ -1,11 +1,23 @@
-import { WasmChip } from "yace";
-import { memory } from "yace/yace_bg.wasm";
+import { WasmChip, WasmKeymap } from "yace";
+import { initAudio, playSamples } from "./audio";
+import { initDebugger, updateDebugger } from "./debugger";
+import { call, onFrame, send } from "./emulator";
+import { initGamepad, loadMapping, pollGamepad } from "./gamepad";
+import { addToLibrary, initLibrary } from "./library";
+import type { Frame } from "./protocol";
+import {
+  loadSettings,
+  loadState,
+  saveSettings,
+  saveState,
+} from "./storage";
 import "./style.css";
 
 const WIDTH = 64;
 const HEIGHT = 32;
 const CELL_SIZE = 5;
 const STEPS_PER_CYCLE = 10;
+const SAVE_SLOTS = 4;
 
 const ROMS = [
   "chip8",
@@ -16,66 +28,51 @@ const ROMS = [
   "space-invaders",
 ];
 
-const keymap: Map<string, number> = new Map([
-  ["Digit1", 0x1],
-  ["Digit2", 0x2],
-  ["Digit3", 0x3],
-  ["Digit4", 0xc],
-  ["KeyQ", 0x4],
-  ["KeyW", 0x5],
-  ["KeyE", 0x6],
-  ["KeyR", 0xd],
-  ["KeyA", 0x7],
-  ["KeyS", 0x8],
-  ["KeyD", 0x9],
-  ["KeyF", 0xe],
-  ["KeyZ", 0xa],
-  ["KeyX", 0x0],
-  ["KeyC", 0xb],
-  ["KeyV", 0xf],
-]);
-
-const chip = new WasmChip();
-
-const displayBuffer = new Uint8Array(
-  memory.buffer,
-  chip.ptr_display_buffer(),
-  WIDTH * HEIGHT
-);
+let keymap = new WasmKeymap("qwerty");
 
 const canvas = document.getElementById("canvas") as HTMLCanvasElement;
 const ctx = canvas.getContext("2d") as CanvasRenderingContext2D;
 
-let currentFrame: number;
+let romHash: string | undefined;
+let romIpf = STEPS_PER_CYCLE;
+let paused = false;
 
-const render = () => {
-  ctx.clearRect(0, 0, WIDTH * CELL_SIZE, HEIGHT * CELL_SIZE);
+// Frames are drawn by the library in the worker, errors pause the emulation
+// so that the state can be inspected
+const onEmulatorFrame = (frame: Frame) => {
+  if (frame.pixels) {
+    const pixels = new Uint8ClampedArray(frame.pixels);
+    ctx.putImageData(new ImageData(pixels, frame.width, frame.height), 0, 0);
+  }
 
-  for (let i = 0; i < displayBuffer.length; i++) {
-    const row = Math.floor(i / WIDTH);
-    const col = i % WIDTH;
-    const color = displayBuffer[i] == 1 ? "#FFF" : "#000";
+  if (frame.debug) {
+    updateDebugger(frame.debug);
+  }
 
-    ctx.fillStyle = color;
-    ctx.fillRect(col * CELL_SIZE, row * CELL_SIZE, CELL_SIZE, CELL_SIZE);
+  if (frame.error) {
+    alert(frame.error);
   }
-};
 
-const loop = () => {
-  const executeCycle = () => {
-    for (let i = 0; i < STEPS_PER_CYCLE; i++) {
-      chip.tick();
-    }
+  playSamples(frame.samples);
+  setPaused(frame.paused);
+};
 
-    chip.update_timers();
-    render();
+const setPaused = (value: boolean) => {
+  const pauseButton = document.getElementById("btn-pause") as HTMLElement;
 
-    currentFrame = requestAnimationFrame(executeCycle);
-  };
+  paused = value;
+  pauseButton.innerText = paused ? "RESUME" : "PAUSE";
+};
 
-  requestAnimationFrame(executeCycle);
+// Gamepads can only be read from the main thread
+const pollInputs = () => {
+  pollGamepad(onKeyDown, onKeyUp);
+  requestAnimationFrame(pollInputs);
 };
 
+const getInput = <T extends HTMLElement>(id: string) =>
+  document.getElementById(id) as T;
+
 const getRom = async (name: string) => {
   const rom = await fetch(`/yace/roms/${name}`);
   const buf = await rom.arrayBuffer();
@@ -88,7 +85,7 @@ const onKeyDown = (key: number | undefined) => {
   if (key != undefined) {
     const keyElem = document.querySelector(`#table-key #key-${key}`);
     keyElem?.classList.add("key-pressed");
-    chip.set_key(key);
+    call("set_key", key);
   }
 };
 
@@ -96,8 +93,308 @@ const onKeyUp = (key: number | undefined) => {
   if (key != undefined) {
     const keyElem = document.querySelector(`#table-key #key-${key}`);
     keyElem?.classList.remove("key-pressed");
-    chip.unset_key(key);
+    call("unset_key", key);
+  }
+};
+
+const updateKeyLabels = () => {
+  for (let key = 0; key < 16; key++) {
+    const keyElem = document.querySelector(`#table-key #key-${key}`);
+
+    if (keyElem instanceof HTMLElement) {
+      keyElem.innerText = keymap.get_name(key).toUpperCase();
+    }
+  }
+};
+
+const updateQuirkChecks = async () => {
+  for (const quirk of WasmChip.quirks()) {
+    const check = document.getElementById(`check-${quirk}`);
+
+    if (check instanceof HTMLInputElement) {
+      check.checked = await call("get_quirk", quirk);
+    }
+  }
+};
+
+const initQuirks = async () => {
+  const container = document.getElementById("quirk-container") as HTMLElement;
+  const platformSelect = document.getElementById(
+    "select-platform"
+  ) as HTMLSelectElement;
+
+  for (const platform of WasmChip.platforms()) {
+    const option = document.createElement("option");
+
+    option.value = platform;
+    option.innerText = WasmChip.platform_name(platform).toUpperCase();
+    platformSelect.appendChild(option);
+  }
+
+  for (const quirk of WasmChip.quirks()) {
+    const label = document.createElement("label");
+    const check = document.createElement("input");
+
+    check.type = "checkbox";
+    check.id = `check-${quirk}`;
+    check.addEventListener("change", async () => {
+      await call("set_quirk", quirk, check.checked);
+      await saveRomSettings();
+    });
+    label.append(check, quirk.replace("_", " ").toUpperCase());
+    container.appendChild(label);
+  }
+
+  platformSelect.addEventListener("change", async () => {
+    await call("set_platform", platformSelect.value);
+    await updateQuirkChecks();
+    await saveRomSettings();
+  });
+
+  await call("set_platform", platformSelect.value);
+  await updateQuirkChecks();
+};
+
+// Known ROMs select their platform, the others use the one suggested by the
+// extension or keep the current one
+const applyPlatform = async (fallback?: string) => {
+  const platformSelect = document.getElementById(
+    "select-platform"
+  ) as HTMLSelectElement;
+  const platform = await call("get_platform");
+
+  if (platform) {
+    platformSelect.value = platform;
+  } else {
+    platformSelect.value = fallback ?? platformSelect.value;
+    await call("set_platform", platformSelect.value);
+  }
+
+  await updateQuirkChecks();
+};
+
+// The platform, quirks, theme and persistence are remembered for each ROM
+const saveRomSettings = async () => {
+  if (!romHash) return;
+
+  const quirks: Record<string, boolean> = {};
+
+  for (const quirk of WasmChip.quirks()) {
+    quirks[quirk] = await call("get_quirk", quirk);
   }
+
+  await saveSettings(romHash, {
+    platform: getInput<HTMLSelectElement>("select-platform").value,
+    quirks,
+    theme: getInput<HTMLSelectElement>("select-theme").value,
+    persistence: getInput<HTMLInputElement>("check-persistence").checked,
+  });
+};
+
+const applyRomSettings = async (hash: string) => {
+  const settings = await loadSettings(hash).catch(() => undefined);
+
+  if (!settings) return;
+
+  const themeSelect = getInput<HTMLSelectElement>("select-theme");
+  const persistenceCheck = getInput<HTMLInputElement>("check-persistence");
+
+  getInput<HTMLSelectElement>("select-platform").value = settings.platform;
+  themeSelect.value = settings.theme;
+  persistenceCheck.checked = settings.persistence;
+
+  for (const [quirk, value] of Object.entries(settings.quirks)) {
+    await call("set_quirk", quirk, value);
+  }
+
+  await call("set_theme", themeSelect.value || undefined);
+  await call("set_persistence", persistenceCheck.checked);
+  await updateQuirkChecks();
+};
+
+const showRomInfo = (name: string, title?: string) => {
+  const info = document.getElementById("rom-info") as HTMLElement;
+  const platformSelect = document.getElementById(
+    "select-platform"
+  ) as HTMLSelectElement;
+  const platform = WasmChip.platform_name(platformSelect.value);
+
+  info.innerText = `${title ?? name} (${platform})`;
+};
+
+// The current game keeps running if the ROM is rejected
+const startRom = async (rom: Uint8Array, name: string) => {
+  try {
+    await call("load", rom);
+  } catch (error) {
+    alert(error);
+    return false;
+  }
+
+  const extension = name.includes(".") ? name.split(".").pop() : undefined;
+
+  // a link to the previous ROM would be misleading
+  history.replaceState(null, "", location.pathname);
+
+  await applyPlatform(
+    extension && WasmChip.platform_from_extension(extension)
+  );
+  await applyRomSettings(await call("get_hash"));
+  await runRom(name, await call("get_tickrate"));
+
+  return true;
+};
+
+// Shared links bring their own quirks, speed and colors
+const startShared = async (code: string) => {
+  let tickrate: number | undefined;
+
+  try {
+    tickrate = await call("load_share_code", code);
+  } catch (error) {
+    alert(error);
+    return;
+  }
+
+  await updateQuirkChecks();
+  await runRom("Shared ROM", tickrate);
+};
+
+const runRom = async (name: string, tickrate?: number) => {
+  const title = await call("get_title");
+
+  romHash = await call("get_hash");
+  romIpf = tickrate ?? STEPS_PER_CYCLE;
+  document.title = `Yace - ${title ?? name}`;
+
+  showRomInfo(name, title);
+  loadMapping(romHash);
+
+  try {
+    send({ type: "audio", sampleRate: await initAudio() });
+  } catch (error) {
+    console.warn(error);
+  }
+
+  await call("power_cycle");
+  send({ type: "start", ipf: romIpf });
+};
+
+// The link is put in the address bar as well in case the clipboard is not
+// available
+const shareRom = async () => {
+  if (!romHash) return;
+
+  let url: string;
+
+  try {
+    const code = await call("get_share_code", romIpf);
+    url = `${location.origin}${location.pathname}#${code}`;
+  } catch (error) {
+    alert(error);
+    return;
+  }
+
+  history.replaceState(null, "", url);
+
+  try {
+    await navigator.clipboard.writeText(url);
+    alert("Link copied to the clipboard");
+  } catch {
+    prompt("Copy the link to share the ROM", url);
+  }
+};
+
+// Opened files are added to the library
+const startFile = async (file: File) => {
+  const rom = new Uint8Array(await file.arrayBuffer());
+
+  if ((await startRom(rom, file.name)) && romHash) {
+    await addToLibrary(romHash, file.name, rom);
+  }
+};
+
+const initFileInput = () => {
+  const openButton = document.getElementById("btn-open");
+  const fileInput = document.getElementById("input-rom") as HTMLInputElement;
+
+  openButton?.addEventListener("click", () => fileInput.click());
+
+  fileInput.addEventListener("change", async () => {
+    const file = fileInput.files?.[0];
+
+    if (file) {
+      await startFile(file);
+    }
+
+    fileInput.value = "";
+  });
+
+  document.addEventListener("dragover", (event) => {
+    event.preventDefault();
+    document.body.classList.add("dragging");
+  });
+
+  document.addEventListener("dragleave", (event) => {
+    if (!event.relatedTarget) {
+      document.body.classList.remove("dragging");
+    }
+  });
+
+  document.addEventListener("drop", async (event) => {
+    const file = event.dataTransfer?.files[0];
+
+    event.preventDefault();
+    document.body.classList.remove("dragging");
+
+    if (file) {
+      await startFile(file);
+    }
+  });
+};
+
+const initSaveStates = () => {
+  const slotSelect = document.getElementById(
+    "select-slot"
+  ) as HTMLSelectElement;
+  const saveButton = document.getElementById("btn-save");
+  const loadButton = document.getElementById("btn-load");
+
+  for (let slot = 1; slot <= SAVE_SLOTS; slot++) {
+    const option = document.createElement("option");
+
+    option.value = slot.toString();
+    option.innerText = `SLOT ${slot}`;
+    slotSelect.appendChild(option);
+  }
+
+  saveButton?.addEventListener("click", async () => {
+    if (romHash) {
+      const slot = parseInt(slotSelect.value);
+      await saveState(romHash, slot, await call("save_state"));
+    }
+  });
+
+  loadButton?.addEventListener("click", async () => {
+    if (!romHash) return;
+
+    const slot = parseInt(slotSelect.value);
+    const state = await loadState(romHash, slot);
+
+    if (!state) {
+      alert(`Slot ${slot} is empty`);
+      return;
+    }
+
+    try {
+      await call("load_state", state);
+    } catch (error) {
+      alert(error);
+      return;
+    }
+
+    await updateQuirkChecks();
+  });
 };
 
 const onTouchDown = (event: Event) => {
@@ -114,8 +411,30 @@ const onTouchUp = (event: Event) => {
 
 const init = () => {
   const button = document.getElementById("btn-start");
+  const resetButton = document.getElementById("btn-reset");
+  const shareButton = document.getElementById("btn-share");
+  const pauseButton = document.getElementById("btn-pause");
+  const stepButton = document.getElementById("btn-step");
+  const clearBreakpointsButton = document.getElementById(
+    "btn-clear-breakpoints"
+  );
   const select = document.getElementById("select-rom") as HTMLSelectElement;
   const keys = document.querySelectorAll("#table-key td");
+  const persistenceCheck = document.getElementById(
+    "check-persistence"
+  ) as HTMLInputElement;
+  const keymapSelect = document.getElementById(
+    "select-keymap"
+  ) as HTMLSelectElement;
+  const themeSelect = document.getElementById(
+    "select-theme"
+  ) as HTMLSelectElement;
+  const volumeRange = document.getElementById(
+    "range-volume"
+  ) as HTMLInputElement;
+  const pitchRange = document.getElementById(
+    "range-pitch"
+  ) as HTMLInputElement;
 
   for (const rom of ROMS) {
     const option = document.createElement("option");
@@ -127,19 +446,57 @@ const init = () => {
 
   select.selectedIndex = 0;
 
+  for (const preset of WasmKeymap.presets()) {
+    const option = document.createElement("option");
+
+    option.value = preset;
+    option.innerText = preset.toUpperCase();
+    keymapSelect.appendChild(option);
+  }
+
+  keymapSelect.addEventListener("change", () => {
+    keymap.free();
+    keymap = new WasmKeymap(keymapSelect.value);
+    updateKeyLabels();
+  });
+
   button?.addEventListener("click", async () => {
     if (select.value) {
       const rom = await getRom(select.value);
 
-      if (currentFrame) {
-        cancelAnimationFrame(currentFrame);
-      }
+      await startRom(rom, select.value);
+    }
+  });
 
-      chip.reset();
-      chip.load(rom);
+  resetButton?.addEventListener("click", () => call("reset"));
+  shareButton?.addEventListener("click", shareRom);
 
-      loop();
-    }
+  persistenceCheck.addEventListener("change", async () => {
+    await call("set_persistence", persistenceCheck.checked);
+    await saveRomSettings();
+  });
+
+  volumeRange.addEventListener("input", () =>
+    call("set_volume", parseInt(volumeRange.value) / 100)
+  );
+  pitchRange.addEventListener("input", () =>
+    call("set_pitch", parseInt(pitchRange.value))
+  );
+
+  call("set_volume", parseInt(volumeRange.value) / 100);
+  call("set_pitch", parseInt(pitchRange.value));
+
+  for (const theme of WasmChip.themes()) {
+    const option = document.createElement("option");
+
+    option.value = theme;
+    option.innerText = theme.replace("-", " ").toUpperCase();
+    themeSelect.appendChild(option);
+  }
+
+  themeSelect.addEventListener("change", async () => {
+    await call("set_theme", themeSelect.value || undefined);
+    await saveRomSettings();
   });
 
   for (const keyElem of keys) {
@@ -149,11 +506,54 @@ const init = () => {
     keyElem.addEventListener("mouseup", onTouchUp);
   }
 
+  initQuirks();
+  initSaveStates();
+  initFileInput();
+  initGamepad();
+  initDebugger();
+  initLibrary(startRom);
+
+  pauseButton?.addEventListener("click", () => {
+    if (romHash) {
+      send({ type: "pause", paused: !paused });
+    }
+  });
+
+  stepButton?.addEventListener("click", () => {
+    if (romHash) {
+      send({ type: "step" });
+    }
+  });
+
+  clearBreakpointsButton?.addEventListener("click", () =>
+    call("clear_breakpoints")
+  );
+
+  onFrame(onEmulatorFrame);
+  call("set_scale", CELL_SIZE);
   canvas.width = WIDTH * CELL_SIZE;
   canvas.height = HEIGHT * CELL_SIZE;
+  pollInputs();
+
+  document.addEventListener("keydown", (e) => {
+    if (e.ctrlKey && e.key == "r") {
+      e.preventDefault();
+      call("reset");
+    } else {
+      onKeyDown(keymap.get_key(e.key));
+    }
+  });
+  document.addEventListener("keyup", (e) => onKeyUp(keymap.get_key(e.key)));
+
+  window.addEventListener("hashchange", () => {
+    if (location.hash.length > 1) {
+      startShared(location.hash.slice(1));
+    }
+  });
 
-  document.addEventListener("keydown", (e) => onKeyDown(keymap.get(e.code)));
-  document.addEventListener("keyup", (e) => onKeyUp(keymap.get(e.code)));
+  if (location.hash.length > 1) {
+    startShared(location.hash.slice(1));
+  }
 };
 
 const fetchRepoData = async () => {
@@ -168,5 +568,16 @@ const fetchRepoData = async () => {
   watcherElem.innerText = parsed.watchers_count;
 };
 
+// Caches the app to keep it working offline, development builds are left
+// out so that the files stay fresh
+const registerServiceWorker = () => {
+  if (import.meta.env.PROD && "serviceWorker" in navigator) {
+    navigator.serviceWorker
+      .register("/yace/sw.js")
+      .catch((error) => console.warn(error));
+  }
+};
+
 init();
+registerServiceWorker();
 fetchRepoData();
@@ -0,0 +1,51 @@
+import type { WasmChip } from "yace";
+
+// Methods of the chip that the main thread can call in the worker
+export type ChipMethod = {
+  [K in keyof WasmChip]: WasmChip[K] extends (...args: any[]) => any
+    ? K
+    : never;
+}[keyof WasmChip];
+
+export type Method<M extends ChipMethod> = Extract<
+  WasmChip[M],
+  (...args: any[]) => any
+>;
+
+export type DebugInfo = {
+  registers: Uint8Array;
+  i: number;
+  pc: number;
+  dt: number;
+  st: number;
+  stack: Uint16Array;
+  breakpoints: Uint16Array;
+  disassemblyStart: number;
+  disassembly: string[];
+  memory: string[];
+};
+
+export type Command =
+  | { type: "call"; id: number; method: ChipMethod; args: unknown[] }
+  | { type: "start"; ipf: number }
+  | { type: "pause"; paused: boolean }
+  | { type: "step" }
+  | { type: "debug"; enabled: boolean }
+  | { type: "audio"; sampleRate: number };
+
+// The pixels are only sent when the display has changed
+export type Frame = {
+  type: "frame";
+  width: number;
+  height: number;
+  pixels?: ArrayBuffer;
+  samples: Float32Array[];
+  debug?: DebugInfo;
+  paused: boolean;
+  error?: string;
+};
+
+export type Message =
+  | { type: "result"; id: number; value: unknown }
+  | { type: "error"; id: number; error: string }
+  | Frame;
@@ -0,0 +1,110 @@
+const DB_NAME = "yace";
+const DB_VERSION = 3;
+const STATE_STORE = "states";
+const GAMEPAD_STORE = "gamepad";
+const ROM_STORE = "roms";
+const SETTINGS_STORE = "settings";
+const STORES = [STATE_STORE, GAMEPAD_STORE, ROM_STORE, SETTINGS_STORE];
+
+export type LibraryRom = {
+  hash: string;
+  name: string;
+  rom: Uint8Array;
+  played: number;
+};
+
+export type RomSettings = {
+  platform: string;
+  quirks: Record<string, boolean>;
+  theme: string;
+  persistence: boolean;
+};
+
+let database: Promise<IDBDatabase> | undefined;
+
+const openDatabase = () => {
+  database ??= new Promise<IDBDatabase>((resolve, reject) => {
+    const request = indexedDB.open(DB_NAME, DB_VERSION);
+
+    request.onupgradeneeded = () => {
+      for (const store of STORES) {
+        if (!request.result.objectStoreNames.contains(store)) {
+          request.result.createObjectStore(store);
+        }
+      }
+    };
+    request.onsuccess = () => resolve(request.result);
+    request.onerror = () => reject(request.error);
+  });
+
+  return database;
+};
+
+const runRequest = async <T>(
+  store: string,
+  mode: IDBTransactionMode,
+  action: (store: IDBObjectStore) => IDBRequest<T>
+) => {
+  const db = await openDatabase();
+
+  return new Promise<T>((resolve, reject) => {
+    const request = action(db.transaction(store, mode).objectStore(store));
+
+    request.onsuccess = () => resolve(request.result);
+    request.onerror = () => reject(request.error);
+  });
+};
+
+// Save states are stored per ROM hash and slot
+const getStateKey = (hash: string, slot: number) => `${hash}/${slot}`;
+
+export const saveState = (hash: string, slot: number, state: Uint8Array) =>
+  runRequest(STATE_STORE, "readwrite", (store) =>
+    store.put(state, getStateKey(hash, slot))
+  );
+
+export const loadState = (hash: string, slot: number) =>
+  runRequest<Uint8Array | undefined>(STATE_STORE, "readonly", (store) =>
+    store.get(getStateKey(hash, slot))
+  );
+
+// Gamepad mappings are stored per ROM hash
+export const saveGamepadMapping = (
+  hash: string,
+  mapping: Record<string, number>
+) =>
+  runRequest(GAMEPAD_STORE, "readwrite", (store) => store.put(mapping, hash));
+
+export const loadGamepadMapping = (hash: string) =>
+  runRequest<Record<string, number> | undefined>(
+    GAMEPAD_STORE,
+    "readonly",
+    (store) => store.get(hash)
+  );
+
+// Library entries, settings and save states are all stored per ROM hash
+export const saveRom = (entry: LibraryRom) =>
+  runRequest(ROM_STORE, "readwrite", (store) => store.put(entry, entry.hash));
+
+export const loadRoms = () =>
+  runRequest<LibraryRom[]>(ROM_STORE, "readonly", (store) => store.getAll());
+
+// Everything saved for the ROM goes away with it
+export const deleteRom = async (hash: string) => {
+  const states = IDBKeyRange.bound(`${hash}/`, `${hash}/\uffff`);
+
+  await runRequest(ROM_STORE, "readwrite", (store) => store.delete(hash));
+  await runRequest(SETTINGS_STORE, "readwrite", (store) => store.delete(hash));
+  await runRequest(GAMEPAD_STORE, "readwrite", (store) => store.delete(hash));
+  await runRequest(STATE_STORE, "readwrite", (store) => store.delete(states));
+};
+
+export const saveSettings = (hash: string, settings: RomSettings) =>
+  runRequest(SETTINGS_STORE, "readwrite", (store) =>
+    store.put(settings, hash)
+  );
+
+export const loadSettings = (hash: string) =>
+  runRequest<RomSettings | undefined>(SETTINGS_STORE, "readonly", (store) =>
+    store.get(hash)
+  );
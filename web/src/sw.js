@@ -0,0 +1,68 @@
+// Keeps the app working offline: the files listed by the build are cached on
+// install, then requests go to the network first and fall back to the cache.
+// The build fills in the version and the file list, each version gets its
+// own cache and the older ones are deleted once it takes over
+const VERSION = "__VERSION__";
+const FILES = ["__FILES__"];
+const PREFIX = "yace-";
+const CACHE = PREFIX + VERSION;
+const BASE = "/yace/";
+
+const precache = async () => {
+  const cache = await caches.open(CACHE);
+
+  await cache.addAll([BASE, ...FILES.map((file) => BASE + file)]);
+};
+
+const deleteOldCaches = async () => {
+  const names = await caches.keys();
+  const old = names.filter((name) => name.startsWith(PREFIX) && name != CACHE);
+
+  await Promise.all(old.map((name) => caches.delete(name)));
+};
+
+// Only the app itself is cached, not the other origins like the GitHub API
+const isAppRequest = (request) => {
+  const url = new URL(request.url);
+
+  return (
+    request.method == "GET" &&
+    url.origin == self.location.origin &&
+    url.pathname.startsWith(BASE)
+  );
+};
+
+const fetchAndCache = async (request) => {
+  const cache = await caches.open(CACHE);
+
+  try {
+    const response = await fetch(request);
+
+    if (response.ok) {
+      await cache.put(request, response.clone());
+    }
+
+    return response;
+  } catch (error) {
+    const cached = await cache.match(request, { ignoreSearch: true });
+
+    if (cached) return cached;
+    throw error;
+  }
+};
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(precache().then(() => self.skipWaiting()));
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(deleteOldCaches().then(() => self.clients.claim()));
+});
+
+self.addEventListener("fetch", (event) => {
+  const { request } = event;
+
+  if (isAppRequest(request)) {
+    event.respondWith(fetchAndCache(request));
+  }
+});
@@ -0,0 +1,178 @@
+import { WasmChip } from "yace";
+import { memory } from "yace/yace_bg.wasm";
+import type { Command, DebugInfo, Frame, Message } from "./protocol";
+
+const FRAME_RATE = 60;
+const FRAME_DURATION = 1000 / FRAME_RATE;
+const MAX_CATCH_UP = 4;
+const DISASSEMBLY_LINES = 16;
+const MEMORY_ROWS = 8;
+
+const chip = new WasmChip();
+
+let ipf = 10;
+let running = false;
+let paused = false;
+let debug = false;
+let dirty = true;
+let sampleRate: number | undefined;
+let error: string | undefined;
+let nextFrame = performance.now();
+
+const post = (message: Message, transfer: Transferable[] = []) =>
+  self.postMessage(message, { transfer });
+
+const getDebugInfo = (): DebugInfo => {
+  const pc = chip.get_pc();
+  const i = chip.get_i();
+  // the current instruction is kept around the middle of the view
+  const disassemblyStart = Math.max(0, pc - DISASSEMBLY_LINES);
+
+  return {
+    registers: chip.get_registers(),
+    i,
+    pc,
+    dt: chip.get_delay_timer(),
+    st: chip.get_sound_timer(),
+    stack: chip.get_stack(),
+    breakpoints: chip.get_breakpoints(),
+    disassemblyStart,
+    disassembly: chip.disassemble(disassemblyStart, DISASSEMBLY_LINES),
+    memory: chip.hexdump(i - (i % 8), MEMORY_ROWS),
+  };
+};
+
+// The RGBA buffer is copied out of the wasm memory so that it can be
+// transferred instead of cloned
+const postFrame = (changed: boolean, samples: Float32Array[]) => {
+  const frame: Frame = {
+    type: "frame",
+    width: chip.rgba_width(),
+    height: chip.rgba_height(),
+    samples,
+    paused,
+    error,
+  };
+  const transfer: Transferable[] = samples.map((chunk) => chunk.buffer);
+
+  if (changed || dirty || chip.has_persistence()) {
+    chip.render_rgba();
+
+    const length = frame.width * frame.height * 4;
+    const pixels = new Uint8Array(
+      memory.buffer,
+      chip.ptr_rgba_buffer(),
+      length
+    );
+
+    frame.pixels = pixels.slice().buffer;
+    transfer.push(frame.pixels);
+  }
+
+  if (debug) {
+    frame.debug = getDebugInfo();
+  }
+
+  dirty = false;
+  error = undefined;
+  post(frame, transfer);
+};
+
+const step = () => {
+  try {
+    chip.step();
+  } catch (reason) {
+    error = String(reason);
+  }
+
+  dirty = true;
+};
+
+// Frames are timed independently from the display refresh rate, late frames
+// are caught up a few at a time and the rest is dropped
+const tick = () => {
+  const now = performance.now();
+  const samples: Float32Array[] = [];
+  let changed = false;
+  let frames = 0;
+
+  while (running && !paused && nextFrame <= now && frames < MAX_CATCH_UP) {
+    try {
+      changed = chip.run_frame(ipf) || changed;
+    } catch (reason) {
+      error = String(reason);
+      paused = true;
+    }
+
+    if (chip.has_hit_breakpoint()) {
+      paused = true;
+    }
+
+    if (sampleRate && chip.is_sound_playing()) {
+      samples.push(chip.generate_samples(Math.round(sampleRate / FRAME_RATE)));
+    }
+
+    nextFrame += FRAME_DURATION;
+    frames++;
+  }
+
+  if (nextFrame <= now) {
+    nextFrame = now + FRAME_DURATION;
+  }
+
+  if (frames > 0 || dirty) {
+    postFrame(changed, samples);
+  }
+
+  setTimeout(tick, nextFrame - performance.now());
+};
+
+const call = (id: number, method: keyof WasmChip, args: unknown[]) => {
+  try {
+    const value = (chip[method] as Function).apply(chip, args);
+    post({ type: "result", id, value });
+  } catch (reason) {
+    post({ type: "error", id, error: String(reason) });
+  }
+
+  dirty = true;
+};
+
+self.onmessage = (event: MessageEvent<Command>) => {
+  const command = event.data;
+
+  switch (command.type) {
+    case "call":
+      call(command.id, command.method, command.args);
+      break;
+    case "start":
+      ipf = command.ipf;
+      running = true;
+      paused = false;
+      nextFrame = performance.now();
+      break;
+    case "pause":
+      // the instruction under a breakpoint would stop the next frame at once
+      if (!command.paused && chip.get_breakpoints().includes(chip.get_pc())) {
+        step();
+      }
+
+      paused = command.paused;
+      dirty = true;
+      break;
+    case "step":
+      paused = true;
+      step();
+      break;
+    case "debug":
+      debug = command.enabled;
+      dirty = true;
+      break;
+    case "audio":
+      sampleRate = command.sampleRate;
+      chip.set_sample_rate(sampleRate);
+      break;
+  }
+};
+
+tick();
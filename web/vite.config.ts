@@ -1,8 +1,69 @@
-import { defineConfig } from "vite";
+import { createHash } from "crypto";
+import { readdirSync, readFileSync } from "fs";
+import { join, relative } from "path";
+import { defineConfig, Plugin } from "vite";
 import wasm from "vite-plugin-wasm";
 import topLevelAwait from "vite-plugin-top-level-await";
 
+const SERVICE_WORKER = "src/sw.js";
+
+const listFiles = (dir: string): string[] =>
+  readdirSync(dir, { withFileTypes: true }).flatMap((entry) => {
+    const path = join(dir, entry.name);
+    return entry.isDirectory() ? listFiles(path) : [path];
+  });
+
+// Emits the service worker with the built and public files to cache, its
+// version is a hash of their content so that every change gets a new cache
+const serviceWorker = (): Plugin => {
+  let root = "";
+  let publicDir = "";
+
+  return {
+    name: "service-worker",
+    apply: "build",
+    enforce: "post",
+    configResolved(config) {
+      root = config.root;
+      publicDir = config.publicDir;
+    },
+    generateBundle(_, bundle) {
+      const contents = new Map<string, string | Uint8Array>();
+
+      for (const path of listFiles(publicDir)) {
+        const file = relative(publicDir, path).split("\\").join("/");
+        contents.set(file, readFileSync(path));
+      }
+
+      for (const [file, output] of Object.entries(bundle)) {
+        contents.set(
+          file,
+          output.type == "chunk" ? output.code : output.source
+        );
+      }
+
+      const source = readFileSync(join(root, SERVICE_WORKER), "utf8");
+      const files = [...contents.keys()].sort();
+      const hash = createHash("sha1").update(source);
+
+      files.forEach((file) => hash.update(contents.get(file) ?? ""));
+
+      this.emitFile({
+        type: "asset",
+        fileName: "sw.js",
+        source: source
+          .replace('"__VERSION__"', JSON.stringify(hash.digest("hex")))
+          .replace('["__FILES__"]', JSON.stringify(files)),
+      });
+    },
+  };
+};
+
 export default defineConfig({
-  plugins: [wasm(), topLevelAwait()],
+  plugins: [wasm(), topLevelAwait(), serviceWorker()],
+  worker: {
+    format: "es",
+    plugins: [wasm(), topLevelAwait()],
+  },
   base: "/yace",
 });